@@ -0,0 +1,23 @@
+use std::path::Path;
+
+const APP_ID_FILE: &str = "steam_appid.txt";
+const DEFAULT_APP_ID: &str = "571880";
+
+fn main() {
+    println!("cargo:rerun-if-changed={}", APP_ID_FILE);
+
+    let app_id = match std::fs::read_to_string(APP_ID_FILE) {
+        Ok(app_id) => app_id,
+        Err(_) => {
+            println!(
+                "cargo:warning={} not found, building with the default App ID {}.",
+                APP_ID_FILE, DEFAULT_APP_ID
+            );
+            DEFAULT_APP_ID.to_string()
+        }
+    };
+
+    let out_dir = std::env::var("OUT_DIR").expect("Cargo did not set OUT_DIR.");
+    std::fs::write(Path::new(&out_dir).join(APP_ID_FILE), app_id.trim())
+        .expect("Failed to write the generated steam_appid.txt.");
+}
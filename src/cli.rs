@@ -0,0 +1,287 @@
+use super::config::Config;
+use super::err_dialog_types::FriendlyMessage;
+use super::item_info::{ItemInfo, ItemInfoState};
+use super::manifest::{self, ExcludeFilter};
+use super::my_steamworks::WorkshopClient;
+use super::profiles::Profile;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::str::FromStr;
+use steamworks::{AppId, PublishedFileId};
+
+const UPLOAD_FLAGS: [&str; 5] = ["id", "name", "folder", "preview", "changenotes"];
+const OTHER_FLAGS: [&str; 1] = ["app-id"];
+const SWITCHES: [&str; 1] = ["json"];
+
+pub const USAGE: &str = "Usage: 4wu [--app-id ID] [--json] [--id ITEM_ID] --name NAME --folder PATH [--preview IMAGE] [--changenotes TEXT]";
+
+#[derive(Debug, Clone)]
+pub struct Args {
+    flags: BTreeMap<String, String>,
+}
+
+impl Args {
+    pub fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut flags = BTreeMap::new();
+
+        while let Some(arg) = args.next() {
+            let flag = match arg.strip_prefix("--") {
+                Some(flag) => flag,
+                None => return Err(format!("Unexpected argument \"{}\".", arg)),
+            };
+
+            let (name, value) = match flag.split_once('=') {
+                None if SWITCHES.contains(&flag) => (flag.to_string(), String::new()),
+                Some((name, value)) => (name.to_string(), value.to_string()),
+                None => match args.next() {
+                    Some(value) => (flag.to_string(), value),
+                    None => return Err(format!("--{} requires a value.", flag)),
+                },
+            };
+
+            if SWITCHES.contains(&name.as_str()) && !value.is_empty() {
+                return Err(format!("--{} doesn't take a value.", name));
+            }
+            if !UPLOAD_FLAGS.contains(&name.as_str())
+                && !OTHER_FLAGS.contains(&name.as_str())
+                && !SWITCHES.contains(&name.as_str())
+            {
+                return Err(format!("Unknown argument \"--{}\".", name));
+            }
+            if flags.insert(name.clone(), value).is_some() {
+                return Err(format!("--{} was given more than once.", name));
+            }
+        }
+
+        Ok(Args { flags })
+    }
+
+    pub fn is_headless(&self) -> bool {
+        UPLOAD_FLAGS
+            .iter()
+            .any(|flag| self.flags.contains_key(*flag))
+    }
+
+    pub fn json(&self) -> bool {
+        self.flags.contains_key("json")
+    }
+
+    pub fn app_id(&self) -> Result<Option<AppId>, String> {
+        self.parse_flag("app-id").map(|id| id.map(AppId))
+    }
+
+    fn parse_flag<T: FromStr>(&self, flag: &str) -> Result<Option<T>, String>
+    where
+        T::Err: fmt::Display,
+    {
+        self.flags
+            .get(flag)
+            .map(|value| {
+                value
+                    .trim()
+                    .parse()
+                    .map_err(|err| format!("Invalid --{} \"{}\": {}.", flag, value, err))
+            })
+            .transpose()
+    }
+
+    fn path(&self, flag: &str) -> PathBuf {
+        self.flags.get(flag).map(PathBuf::from).unwrap_or_default()
+    }
+
+    pub fn upload(&self) -> Result<(Option<PublishedFileId>, ItemInfo), String> {
+        let item_id = self.parse_flag("id")?.map(PublishedFileId);
+
+        let item_info = ItemInfo {
+            name: self.flags.get("name").cloned().unwrap_or_default(),
+            preview_image: self.path("preview"),
+            target_folder: self.path("folder"),
+            change_notes: self.flags.get("changenotes").cloned().unwrap_or_default(),
+            ..Default::default()
+        };
+
+        ItemInfoState::from(item_info)
+            .validate_now(item_id)
+            .map(|item_info| (item_id, item_info))
+            .map_err(|errors| errors.to_string())
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct UploadReport {
+    action: &'static str,
+    item_id: Option<u64>,
+    url: Option<String>,
+    error: Option<String>,
+}
+
+impl UploadReport {
+    fn new(action: &'static str, item_id: Option<PublishedFileId>) -> Self {
+        UploadReport {
+            action,
+            item_id: item_id.map(|id| id.0),
+            url: item_id.map(super::item_url),
+            error: None,
+        }
+    }
+
+    fn failed(mut self, error: String) -> Self {
+        self.error = Some(error);
+        self
+    }
+}
+
+pub fn run_upload(
+    client: WorkshopClient,
+    item_id: Option<PublishedFileId>,
+    item_info: ItemInfo,
+    config: &Config,
+    json: bool,
+) -> i32 {
+    let report = upload_item(client, item_id, item_info, config);
+
+    if let Err(err) = write_report(&mut std::io::stdout().lock(), &report, json) {
+        log::error!("Failed to write the upload report: {}", err);
+    }
+
+    match report.error {
+        Some(_) => 1,
+        None => 0,
+    }
+}
+
+fn write_report(out: &mut impl Write, report: &UploadReport, json: bool) -> io::Result<()> {
+    if json {
+        serde_json::to_writer(&mut *out, report)?;
+        return writeln!(out);
+    }
+
+    match (&report.error, report.item_id) {
+        (Some(error), _) => eprintln!("{}", error),
+        (None, Some(item_id)) => writeln!(out, "{}", item_id)?,
+        (None, None) => (),
+    }
+    Ok(())
+}
+
+fn upload_item(
+    client: WorkshopClient,
+    item_id: Option<PublishedFileId>,
+    item_info: ItemInfo,
+    config: &Config,
+) -> UploadReport {
+    let action = match item_id {
+        Some(_) => "updated",
+        None => "created",
+    };
+
+    if !client.is_online() {
+        return UploadReport::new(action, item_id).failed(format!(
+            "Steam appears to be offline. \"{}\" can't be uploaded until Steam reconnects to its servers.",
+            item_info.name
+        ));
+    }
+
+    let item_id = match item_id {
+        Some(item_id) => item_id,
+        None => match smol::block_on(client.clone().create_item(config.steam_timeout())) {
+            Ok((item_id, _)) => {
+                eprintln!("Created item {}.", item_id.0);
+                Profile::pending(item_id, item_info.clone()).save_pending_upload();
+                item_id
+            }
+            Err(err) => {
+                return UploadReport::new(action, None).failed(format!(
+                    "Error creating a new entry on the workshop: {}",
+                    err.friendly_message()
+                ));
+            }
+        },
+    };
+
+    let filter = ExcludeFilter {
+        hidden: false,
+        patterns: config.exclude_patterns.clone(),
+    };
+    let staging_dir = manifest::staging_dir(item_id);
+    let result = smol::block_on(client.send_filtered_item(
+        item_id,
+        item_info,
+        filter,
+        Some(staging_dir.clone()),
+    ));
+    if staging_dir.exists() {
+        if let Err(error) = std::fs::remove_dir_all(&staging_dir) {
+            log::warn!(
+                "Failed to remove staged upload folder {:?}: {}",
+                staging_dir,
+                error
+            );
+        }
+    }
+
+    match result {
+        Ok((item_id, needs_legal_agreement)) => {
+            if Profile::load_pending_upload()
+                .is_some_and(|pending| pending.item_id() == Some(item_id))
+            {
+                Profile::clear_pending_upload();
+            }
+            if needs_legal_agreement {
+                eprintln!("You still need to accept the Steam workshop legal agreement before your items can be seen by others.");
+            }
+            UploadReport::new(action, Some(item_id))
+        }
+        Err(err) => UploadReport::new(action, Some(item_id))
+            .failed(format!("Error uploading item {}: {}", item_id.0, err)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Args, String> {
+        Args::parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn json_is_a_switch() {
+        let args = parse(&["--json", "--name", "Mod"]).unwrap();
+        assert!(args.json());
+        assert!(args.is_headless());
+        assert!(!parse(&["--name", "Mod"]).unwrap().json());
+        assert!(parse(&["--json=yes"]).is_err());
+    }
+
+    #[test]
+    fn report_keeps_the_error() {
+        let report = UploadReport::new("updated", Some(PublishedFileId(123)))
+            .failed("Steam appears to be offline.".to_string());
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["action"], "updated");
+        assert_eq!(json["item_id"], 123);
+        assert_eq!(
+            json["url"],
+            "https://steamcommunity.com/sharedfiles/filedetails/?id=123"
+        );
+        assert_eq!(json["error"], "Steam appears to be offline.");
+    }
+
+    #[test]
+    fn json_output_is_only_the_report() {
+        let report = UploadReport::new("created", Some(PublishedFileId(123)));
+        let mut out = Vec::new();
+        write_report(&mut out, &report, true).unwrap();
+
+        let out = String::from_utf8(out).unwrap();
+        assert_eq!(out.lines().count(), 1);
+        let json: serde_json::Value = serde_json::from_str(&out).unwrap();
+        assert!(json.is_object());
+        assert_eq!(json["item_id"], 123);
+        assert!(json["error"].is_null());
+    }
+}
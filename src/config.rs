@@ -0,0 +1,123 @@
+use super::storage::{config_dir, read_json, write_json};
+use super::theme::ThemeChoice;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::path::PathBuf;
+use std::time::Duration;
+
+const DEFAULT_CALLBACK_POLL_MS: u64 = 10;
+const MIN_CALLBACK_POLL_MS: u64 = 1;
+const DEFAULT_STEAM_TIMEOUT_SECS: u64 = 30;
+const DEFAULT_ITEM_CACHE_SECS: u32 = 360;
+const DEFAULT_WINDOW_SIZE: (u32, u32) = (300, 400);
+const MIN_WINDOW_SIZE: u32 = 200;
+const MAX_WINDOW_SIZE: u32 = 16384;
+const MIN_WINDOW_POSITION: i32 = -16384;
+const DEFAULT_EXCLUDE_PATTERNS: [&str; 7] = [
+    ".DS_Store",
+    "Thumbs.db",
+    "desktop.ini",
+    "*.tmp",
+    "*~",
+    ".git/",
+    "__MACOSX/",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub show_error_details: bool,
+    pub allowed_app_ids: BTreeSet<u32>,
+    pub callback_poll_ms: u64,
+    pub steam_timeout_secs: u64,
+    pub item_cache_secs: u32,
+    pub last_target_folder: Option<PathBuf>,
+    pub last_preview_image: Option<PathBuf>,
+    pub window_size: Option<(u32, u32)>,
+    pub window_position: Option<(i32, i32)>,
+    pub theme: Option<ThemeChoice>,
+    pub exclude_patterns: Vec<String>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            show_error_details: false,
+            allowed_app_ids: BTreeSet::new(),
+            callback_poll_ms: DEFAULT_CALLBACK_POLL_MS,
+            steam_timeout_secs: DEFAULT_STEAM_TIMEOUT_SECS,
+            item_cache_secs: DEFAULT_ITEM_CACHE_SECS,
+            last_target_folder: None,
+            last_preview_image: None,
+            window_size: None,
+            window_position: None,
+            theme: None,
+            exclude_patterns: DEFAULT_EXCLUDE_PATTERNS
+                .iter()
+                .map(|pattern| pattern.to_string())
+                .collect(),
+        }
+    }
+}
+
+impl Config {
+    fn path() -> Option<PathBuf> {
+        config_dir().map(|dir| dir.join("config.json"))
+    }
+
+    pub fn load() -> Self {
+        Config::path()
+            .and_then(|path| read_json(&path))
+            .unwrap_or_default()
+    }
+
+    pub fn callback_poll_interval(&self) -> Duration {
+        Duration::from_millis(self.callback_poll_ms.max(MIN_CALLBACK_POLL_MS))
+    }
+
+    pub fn steam_timeout(&self) -> Duration {
+        Duration::from_secs(self.steam_timeout_secs)
+    }
+
+    pub fn window_size(&self) -> (u32, u32) {
+        self.window_size
+            .filter(|&(width, height)| valid_window_size(width) && valid_window_size(height))
+            .unwrap_or(DEFAULT_WINDOW_SIZE)
+    }
+
+    pub fn window_position(&self) -> Option<(i32, i32)> {
+        self.window_position
+            .filter(|&(x, y)| valid_window_position(x) && valid_window_position(y))
+    }
+
+    pub fn set_window_size(&mut self, width: u32, height: u32) {
+        if valid_window_size(width) && valid_window_size(height) {
+            self.window_size = Some((width, height));
+        }
+    }
+
+    pub fn set_window_position(&mut self, x: i32, y: i32) {
+        if valid_window_position(x) && valid_window_position(y) {
+            self.window_position = Some((x, y));
+        }
+    }
+
+    pub fn save(&self) {
+        let result = match Config::path() {
+            Some(path) => write_json(&path, self),
+            None => return,
+        };
+
+        if let Err(error) = result {
+            log::warn!("Failed to save config: {}", error);
+        }
+    }
+}
+
+fn valid_window_size(size: u32) -> bool {
+    (MIN_WINDOW_SIZE..=MAX_WINDOW_SIZE).contains(&size)
+}
+
+fn valid_window_position(position: i32) -> bool {
+    (MIN_WINDOW_POSITION..=MAX_WINDOW_SIZE as i32).contains(&position)
+}
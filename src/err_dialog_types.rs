@@ -1,4 +1,7 @@
+use steamworks::SteamError;
+
 pub fn error_dialog(msg: &str) {
+    log::error!("{}", msg);
     let _ = native_dialog::MessageDialog::new()
         .set_type(native_dialog::MessageType::Error)
         .set_title("Error")
@@ -7,6 +10,7 @@ pub fn error_dialog(msg: &str) {
 }
 
 pub fn confirm_dialog(msg: &str) -> bool {
+    log::info!("Confirming: {}", msg);
     let ans = native_dialog::MessageDialog::new()
         .set_type(native_dialog::MessageType::Warning)
         .set_title("Warning")
@@ -14,7 +18,10 @@ pub fn confirm_dialog(msg: &str) -> bool {
         .show_confirm();
 
     match ans {
-        Ok(b) => b,
+        Ok(b) => {
+            log::info!("Confirmation answered: {}", b);
+            b
+        }
         Err(e) => {
             error_dialog(
                 format!("Error retrieving confirmation: {:?}\nAssuming False...", e).as_str(),
@@ -24,26 +31,20 @@ pub fn confirm_dialog(msg: &str) -> bool {
     }
 }
 
-pub trait ErrorDialogUnwrapper<T> {
-    fn expect_or_dialog(self, msg: &str) -> T;
-}
-
-impl<T> ErrorDialogUnwrapper<T> for Option<T> {
-    #[track_caller]
-    fn expect_or_dialog(self, msg: &str) -> T {
-        self.unwrap_or_else(|| {
-            error_dialog(msg);
-            None.expect(msg)
-        })
-    }
+pub trait FriendlyMessage {
+    fn friendly_message(&self) -> String;
 }
 
-impl<T, E: std::fmt::Debug> ErrorDialogUnwrapper<T> for Result<T, E> {
-    #[track_caller]
-    fn expect_or_dialog(self, msg: &str) -> T {
-        self.unwrap_or_else(|e| {
-            error_dialog(format!("{} {:?}", msg, &e).as_str());
-            Err(e).expect(msg)
-        })
+impl FriendlyMessage for SteamError {
+    fn friendly_message(&self) -> String {
+        match self {
+            SteamError::AccessDenied => "Steam denied access. Make sure you're signed in to the account that owns this item and have accepted the Workshop legal agreement.".to_string(),
+            SteamError::Timeout => "Steam didn't respond in time. Check your internet connection and that Steam is online, then try again.".to_string(),
+            SteamError::NoMatch => "No matching Workshop item was found. Check the item ID and that the item hasn't been deleted.".to_string(),
+            SteamError::Cancelled => "The operation was cancelled before Steam finished it.".to_string(),
+            SteamError::Banned => "Steam reports a ban on this item or account. Check the item's page on Steam for details.".to_string(),
+            SteamError::LimitExceeded => "A Workshop limit was exceeded, such as your cloud storage quota. Free up space or reduce the upload size and try again.".to_string(),
+            err => err.to_string(),
+        }
     }
 }
@@ -1,18 +1,66 @@
 use super::err_dialog_types::error_dialog;
-use iced::widget::{button, column, row, text, text_input};
-use iced::Element;
+use iced::widget::{button, column, row, text, text_input, tooltip};
+use iced::{Command, Element};
 use native_dialog::FileDialog;
 use std::path::PathBuf;
 
+const DISPLAY_PATH_LEN: usize = 32;
+pub const PREVIEW_EXTENSIONS: &[&str] = &["jpg", "jpeg", "png", "gif"];
+
+pub fn truncate_middle(path: &str, max_len: usize) -> String {
+    const ELLIPSIS: &str = "...";
+
+    if path.chars().count() <= max_len {
+        return path.to_string();
+    }
+
+    let sep = if path.contains('\\') { '\\' } else { '/' };
+    let parts: Vec<&str> = path.split(sep).collect();
+
+    if parts.len() > 3 {
+        let head = parts[..2].join(&sep.to_string());
+        let mut tail: Vec<&str> = vec![parts[parts.len() - 1]];
+        let mut len = head.chars().count() + ELLIPSIS.len() + 2 + tail[0].chars().count();
+
+        for part in parts[2..parts.len() - 1].iter().rev() {
+            len += part.chars().count() + 1;
+            if len > max_len {
+                break;
+            }
+            tail.insert(0, part);
+        }
+
+        let shortened = format!(
+            "{}{}{}{}{}",
+            head,
+            sep,
+            ELLIPSIS,
+            sep,
+            tail.join(&sep.to_string())
+        );
+        if shortened.chars().count() <= max_len {
+            return shortened;
+        }
+    }
+
+    let keep = max_len.saturating_sub(ELLIPSIS.len());
+    let chars: Vec<char> = path.chars().collect();
+    let head: String = chars[..keep / 2].iter().collect();
+    let tail: String = chars[chars.len() - (keep - keep / 2)..].iter().collect();
+    format!("{}{}{}", head, ELLIPSIS, tail)
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct FileField {
     pub path: PathBuf,
+    browsing: bool,
 }
 
 impl FileField {
     pub fn new() -> Self {
         FileField {
             path: PathBuf::new(),
+            browsing: false,
         }
     }
 
@@ -22,20 +70,60 @@ impl FileField {
         placeholder: &str,
         edit_msg: fn(String) -> Message,
         browse_msg: Message,
+        copy_msg: Message,
+        open_msg: Option<Message>,
     ) -> Element<'a, Message> {
-        column![
-            text(label),
-            row![
-                text_input(placeholder, &self.path.to_string_lossy(), edit_msg),
-                button("Browse",).on_press(browse_msg),
-            ],
-        ]
-        .into()
+        let full_path = self.path.to_string_lossy();
+
+        let mut copy_button = button("Copy");
+        if !full_path.is_empty() {
+            copy_button = copy_button.on_press(copy_msg);
+        }
+
+        let mut buttons = row![
+            text_input(placeholder, &full_path, edit_msg),
+            button("Browse",).on_press(browse_msg),
+            copy_button,
+        ];
+        if let Some(open_msg) = open_msg {
+            buttons = buttons.push(button("Open").on_press(open_msg));
+        }
+
+        let mut res = column![text(label), buttons];
+
+        if !full_path.is_empty() {
+            res = res.push(tooltip(
+                text(truncate_middle(&full_path, DISPLAY_PATH_LEN)).size(14),
+                &full_path,
+                tooltip::Position::Bottom,
+            ));
+        }
+
+        res.into()
+    }
+
+    pub fn resolved_path(&self) -> String {
+        let path = self
+            .path
+            .canonicalize()
+            .unwrap_or_else(|_| self.path.clone())
+            .to_string_lossy()
+            .into_owned();
+
+        match path.strip_prefix(r"\\?\") {
+            Some(stripped) => stripped.to_string(),
+            None => path,
+        }
     }
 
-    pub fn select_file(&mut self) {
+    pub fn select_file<Message: Send + 'static>(&mut self, done_msg: Message) -> Command<Message> {
+        if self.browsing {
+            return Command::none();
+        }
+        self.browsing = true;
+
         let result = FileDialog::new()
-            .add_filter("JPG Files", &["jpg", "jpeg"])
+            .add_filter("Image Files", PREVIEW_EXTENSIONS)
             .show_open_single_file();
 
         if let Ok(pathbuf) = result {
@@ -47,9 +135,16 @@ impl FileField {
                 format!("Failed to select file. Error: {:?}", result.err().unwrap()).as_str(),
             );
         }
+
+        Command::perform(async {}, move |_| done_msg)
     }
 
-    pub fn select_dir(&mut self) {
+    pub fn select_dir<Message: Send + 'static>(&mut self, done_msg: Message) -> Command<Message> {
+        if self.browsing {
+            return Command::none();
+        }
+        self.browsing = true;
+
         let result = FileDialog::new().show_open_single_dir();
 
         if let Ok(pathbuf) = result {
@@ -65,12 +160,35 @@ impl FileField {
                 .as_str(),
             );
         }
+
+        Command::perform(async {}, move |_| done_msg)
+    }
+
+    pub fn open(&self) {
+        if let Err(error) = open::that(&self.path) {
+            log::warn!("Failed to open {:?}: {}", self.path, error);
+            error_dialog(
+                format!(
+                    "Could not open \"{}\"\nError: {}",
+                    self.path.to_string_lossy(),
+                    error
+                )
+                .as_str(),
+            );
+        }
+    }
+
+    pub fn finish_browsing(&mut self) {
+        self.browsing = false;
     }
 }
 
 impl From<PathBuf> for FileField {
     fn from(path: PathBuf) -> Self {
-        FileField { path }
+        FileField {
+            path,
+            browsing: false,
+        }
     }
 }
 
@@ -78,6 +196,7 @@ impl From<String> for FileField {
     fn from(path: String) -> Self {
         FileField {
             path: PathBuf::from(path),
+            browsing: false,
         }
     }
 }
@@ -86,6 +205,36 @@ impl From<&str> for FileField {
     fn from(path: &str) -> Self {
         FileField {
             path: PathBuf::from(path),
+            browsing: false,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn truncate_middle_keeps_short_paths() {
+        assert_eq!(truncate_middle(r"C:\Mods\content", 32), r"C:\Mods\content");
+    }
+
+    #[test]
+    fn truncate_middle_drops_middle_components() {
+        let path = r"C:\Users\someone\Documents\Mods\MyMod\content";
+        assert_eq!(
+            truncate_middle(path, 32),
+            r"C:\Users\...\Mods\MyMod\content"
+        );
+    }
+
+    #[test]
+    fn truncate_middle_shortens_long_last_component() {
+        let path = "/home/someone/mods/a_very_long_folder_name_for_the_mod_content";
+        let truncated = truncate_middle(path, 32);
+        assert_eq!(truncated.chars().count(), 32);
+        assert!(truncated.starts_with("/home/someo"));
+        assert!(truncated.contains("..."));
+        assert!(truncated.ends_with("mod_content"));
+    }
+}
@@ -1,8 +1,20 @@
-use super::file_field::FileField;
-use iced::widget::{column, text, text_input};
-use iced::Element;
-use std::path::PathBuf;
-use steamworks::{PublishedFileId, QueryResult};
+use super::file_field::{FileField, PREVIEW_EXTENSIONS};
+use super::manifest::format_bytes;
+use super::preview;
+use super::validated_input::{validated_text_input, ERROR_COLOR};
+use super::versions::bump_patch;
+use iced::widget::{button, checkbox, column, image, pick_list, row, text, text_input};
+use iced::{Command, Element, Length};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use steamworks::{PublishedFileId, PublishedFileVisibility, QueryResult};
+use unicode_normalization::UnicodeNormalization;
+
+const THUMBNAIL_SIZE: u16 = 128;
+const MAX_NAME_BYTES: usize = 128;
+const VALIDATION_DELAY: Duration = Duration::from_millis(300);
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ItemInfoMessage {
@@ -11,15 +23,136 @@ pub enum ItemInfoMessage {
     EditTargetFolder(String),
     BrowsePreviewImage,
     BrowseTargetFolder,
-    EditChangeNotes(String),
+    PreviewImageBrowseDone,
+    TargetFolderBrowseDone,
+    CopyPreviewImagePath,
+    CopyTargetFolderPath,
+    OpenTargetFolder,
+    EditChangeNotesLine(usize, String),
+    InsertChangeNotesLine(usize),
+    RemoveChangeNotesLine(usize),
+    EditDescription(String),
+    SetVisibility(Visibility),
+    EditTagInput(String),
+    AddTag,
+    RemoveTag(usize),
+    ResetName,
+    ResetDescription,
+    ResetTags,
+    SetCropPreview(bool),
+    SetPrefixVersion(bool),
+    EditVersion(String),
+    PathsChecked(PathChecks),
+    EditSettled(u64),
+    SetMetadataOnly(bool),
+    ToggleAdvanced,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PathChecks {
+    preview_image: PathBuf,
+    preview_result: Result<(), String>,
+    preview_size: Option<(u32, u32)>,
+    target_folder: PathBuf,
+    folder_result: Result<(), String>,
+    folder_is_dir: bool,
+}
+
+impl PathChecks {
+    fn run(preview_image: PathBuf, target_folder: PathBuf) -> Self {
+        let folder_result = if target_folder.exists() {
+            Ok(())
+        } else {
+            Err(format!(
+                "Target folder \"{}\" does not exist.",
+                target_folder.to_string_lossy()
+            ))
+        };
+
+        PathChecks {
+            preview_result: validate_preview_path(&preview_image),
+            preview_size: preview::dimensions(&preview_image),
+            preview_image,
+            folder_result,
+            folder_is_dir: target_folder.is_dir(),
+            target_folder,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    Name,
+    PreviewImage,
+    TargetFolder,
+    Tags,
+    Version,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FieldErrors(Vec<(Field, String)>);
+
+impl FieldErrors {
+    fn check(&mut self, field: Field, result: Result<(), String>) {
+        if let Err(error) = result {
+            self.0.push((field, error));
+        }
+    }
+
+    pub fn get(&self, field: Field) -> Option<&str> {
+        self.0
+            .iter()
+            .find(|(error_field, _)| *error_field == field)
+            .map(|(_, error)| error.as_str())
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl fmt::Display for FieldErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let errors: Vec<&str> = self.0.iter().map(|(_, error)| error.as_str()).collect();
+        write!(f, "{}", errors.join("\n"))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CurrentPreview(image::Handle);
+
+impl PartialEq for CurrentPreview {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.id() == other.0.id()
+    }
+}
+
+impl Eq for CurrentPreview {}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ItemInfoState {
     name: String,
     preview_image: FileField,
+    path_checks: Option<PathChecks>,
+    edit_serial: u64,
+    editing: Option<Field>,
+    crop_preview: bool,
     target_folder: FileField,
+    metadata_only: bool,
+    description: String,
+    visibility: Visibility,
+    tags: Vec<String>,
+    tag_input: String,
     change_notes: String,
+    prefix_version: bool,
+    version: String,
+    original: Option<ItemInfo>,
+    current_preview: Option<CurrentPreview>,
+    show_advanced: bool,
 }
 
 impl Default for ItemInfoState {
@@ -27,68 +160,916 @@ impl Default for ItemInfoState {
         ItemInfoState {
             name: String::new(),
             preview_image: FileField::new(),
+            path_checks: None,
+            edit_serial: 0,
+            editing: None,
+            crop_preview: false,
             target_folder: FileField::new(),
+            metadata_only: false,
+            description: String::new(),
+            visibility: Visibility::Public,
+            tags: Vec::new(),
+            tag_input: String::new(),
             change_notes: String::new(),
+            prefix_version: false,
+            version: String::new(),
+            original: None,
+            current_preview: None,
+            show_advanced: false,
         }
     }
 }
 
 impl ItemInfoState {
-    pub fn update(&mut self, message: ItemInfoMessage) {
+    pub fn from_original(original: ItemInfo) -> Self {
+        ItemInfoState {
+            original: Some(original.clone()),
+            ..original.into()
+        }
+    }
+
+    pub fn with_paths(preview_image: Option<PathBuf>, target_folder: Option<PathBuf>) -> Self {
+        ItemInfoState {
+            preview_image: preview_image
+                .map(FileField::from)
+                .unwrap_or_else(FileField::new),
+            target_folder: target_folder
+                .map(FileField::from)
+                .unwrap_or_else(FileField::new),
+            ..ItemInfoState::default()
+        }
+    }
+
+    pub fn validate(&self, file_id: Option<PublishedFileId>) -> Result<ItemInfo, FieldErrors> {
+        let mut state = self.clone();
+        state.metadata_only &= file_id.is_some();
+        let result = ItemInfo::try_from(state);
+        if file_id.is_none() && self.preview_image.path.as_os_str().is_empty() {
+            let mut errors = result.err().unwrap_or_default();
+            errors.check(
+                Field::PreviewImage,
+                Err("A preview image is required for new items.".to_string()),
+            );
+            return Err(errors);
+        }
+
+        result
+    }
+
+    pub fn field_errors(&self, file_id: Option<PublishedFileId>) -> FieldErrors {
+        self.validate(file_id).err().unwrap_or_default()
+    }
+
+    pub fn validate_preview(&self) -> Result<PathBuf, String> {
+        if self.preview_image.path.as_os_str().is_empty() {
+            return Err("Choose a preview image to update.".to_string());
+        }
+
+        self.preview_result()?;
+        Ok(self.preview_image.path.clone())
+    }
+
+    pub fn debug_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "name": self.name,
+            "preview_image": self.preview_image.path.to_string_lossy(),
+            "target_folder": self.target_folder.path.to_string_lossy(),
+            "description": self.description,
+            "visibility": self.visibility.to_string(),
+            "tags": self.tags,
+            "change_notes": self.change_notes,
+            "crop_preview": self.crop_preview,
+            "version": self.prefix_version.then_some(self.version.as_str()),
+            "original_name": self.original.as_ref().map(|original| original.name.as_str()),
+        })
+    }
+
+    pub fn has_edits_since(&self, start: &ItemInfoState) -> bool {
+        self.name != start.name
+            || self.preview_image.path != start.preview_image.path
+            || self.crop_preview != start.crop_preview
+            || self.target_folder.path != start.target_folder.path
+            || self.metadata_only != start.metadata_only
+            || self.description != start.description
+            || self.visibility != start.visibility
+            || self.tags != start.tags
+            || self.tag_input != start.tag_input
+            || self.change_notes != start.change_notes
+            || self.prefix_version != start.prefix_version
+            || self.version != start.version
+    }
+
+    pub fn load_preset(&mut self, item_info: ItemInfo) {
+        *self = ItemInfoState {
+            original: self.original.take(),
+            current_preview: self.current_preview.take(),
+            show_advanced: self.show_advanced,
+            ..item_info.into()
+        };
+    }
+
+    pub fn suggest_version(&mut self, last_version: &str) {
+        if self.version.is_empty() {
+            self.version = bump_patch(last_version);
+            self.prefix_version = true;
+        }
+    }
+
+    pub fn set_current_preview(&mut self, bytes: Vec<u8>) {
+        self.current_preview = Some(CurrentPreview(image::Handle::from_memory(bytes)));
+    }
+
+    pub fn crop_preview(&self) -> bool {
+        self.crop_preview
+    }
+
+    pub fn original_name(&self) -> Option<&str> {
+        self.original
+            .as_ref()
+            .map(|original| original.name.as_str())
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn metadata_only(&self) -> bool {
+        self.metadata_only
+    }
+
+    pub fn target_folder(&self) -> &Path {
+        &self.target_folder.path
+    }
+
+    pub fn preview_check(&self, file_id: Option<PublishedFileId>) -> Result<String, String> {
+        let path = &self.preview_image.path;
+        if path.as_os_str().is_empty() {
+            return match file_id {
+                Some(_) => Ok("No preview chosen; the current image is kept.".to_string()),
+                None => Err("A preview image is required for new items.".to_string()),
+            };
+        }
+
+        validate_preview_path(path)?;
+
+        let size = path.metadata().map(|metadata| metadata.len()).unwrap_or(0);
+        let mut report = match self.preview_size() {
+            Some((width, height)) => format!(
+                "Preview image is {}x{}, {}.",
+                width,
+                height,
+                format_bytes(size)
+            ),
+            None => return Err("Preview image could not be decoded.".to_string()),
+        };
+        if self.non_square_preview().is_some() {
+            report.push_str(if self.crop_preview {
+                " It will be cropped to a square."
+            } else {
+                " It is not square."
+            });
+        }
+        Ok(report)
+    }
+
+    fn non_square_preview(&self) -> Option<(u32, u32)> {
+        self.preview_size()
+            .filter(|&size| !preview::is_square(size))
+    }
+
+    fn preview_checks(&self) -> Option<&PathChecks> {
+        self.path_checks
+            .as_ref()
+            .filter(|checks| checks.preview_image == self.preview_image.path)
+    }
+
+    fn folder_checks(&self) -> Option<&PathChecks> {
+        self.path_checks
+            .as_ref()
+            .filter(|checks| checks.target_folder == self.target_folder.path)
+    }
+
+    fn preview_size(&self) -> Option<(u32, u32)> {
+        self.preview_checks().and_then(|checks| checks.preview_size)
+    }
+
+    fn preview_result(&self) -> Result<(), String> {
+        if self.preview_image.path.as_os_str().is_empty() {
+            return Ok(());
+        }
+
+        match self.preview_checks() {
+            Some(checks) => checks.preview_result.clone(),
+            None => Err("Checking preview image...".to_string()),
+        }
+    }
+
+    fn folder_result(&self) -> Result<(), String> {
+        if self.target_folder.path.as_os_str().is_empty() {
+            return Err("Target folder cannot be empty.".to_string());
+        }
+
+        match self.folder_checks() {
+            Some(checks) => checks.folder_result.clone(),
+            None => Err("Checking target folder...".to_string()),
+        }
+    }
+
+    fn check_paths_now(&mut self) {
+        let checks = PathChecks::run(
+            self.preview_image.path.clone(),
+            self.target_folder.path.clone(),
+        );
+        self.apply_path_checks(checks);
+    }
+
+    pub fn validate_now(
+        &mut self,
+        file_id: Option<PublishedFileId>,
+    ) -> Result<ItemInfo, FieldErrors> {
+        self.editing = None;
+        self.check_paths_now();
+        self.validate(file_id)
+    }
+
+    fn debounce(&mut self, field: Field) -> Command<ItemInfoMessage> {
+        self.edit_serial += 1;
+        self.editing = Some(field);
+
+        let serial = self.edit_serial;
+        Command::perform(smol::Timer::after(VALIDATION_DELAY), move |_| {
+            ItemInfoMessage::EditSettled(serial)
+        })
+    }
+
+    pub fn check_paths(&self) -> Command<ItemInfoMessage> {
+        let preview_image = self.preview_image.path.clone();
+        let target_folder = self.target_folder.path.clone();
+        Command::perform(
+            smol::unblock(move || PathChecks::run(preview_image, target_folder)),
+            ItemInfoMessage::PathsChecked,
+        )
+    }
+
+    fn apply_path_checks(&mut self, checks: PathChecks) {
+        self.path_checks = Some(checks);
+
+        if self.preview_checks().is_some() && self.non_square_preview().is_none() {
+            self.crop_preview = false;
+        }
+        self.autofill_name();
+    }
+
+    fn autofill_name(&mut self) {
+        // Only for new items, and never over a name the user already typed.
+        if self.original.is_some() || !self.name.trim().is_empty() {
+            return;
+        }
+
+        let folder_is_dir = self
+            .folder_checks()
+            .is_some_and(|checks| checks.folder_is_dir);
+        let folder = &self.target_folder.path;
+        if let Some(folder_name) = folder.file_name().filter(|_| folder_is_dir) {
+            self.name = folder_name.to_string_lossy().into_owned();
+        }
+    }
+
+    pub fn update(&mut self, message: ItemInfoMessage) -> Command<ItemInfoMessage> {
         match message {
-            ItemInfoMessage::EditName(new_name) => self.name = new_name,
+            ItemInfoMessage::ToggleAdvanced => {
+                self.show_advanced = !self.show_advanced;
+            }
+            ItemInfoMessage::EditName(new_name) => {
+                self.name = new_name;
+                return self.debounce(Field::Name);
+            }
             ItemInfoMessage::EditPreviewImage(new_path) => {
-                self.preview_image = FileField::from(new_path)
+                self.preview_image = FileField::from(new_path);
+                return self.debounce(Field::PreviewImage);
             }
             ItemInfoMessage::EditTargetFolder(new_path) => {
-                self.target_folder = FileField::from(new_path)
+                self.target_folder = FileField::from(new_path);
+                return self.debounce(Field::TargetFolder);
             }
             ItemInfoMessage::BrowsePreviewImage => {
-                self.preview_image.select_file();
+                return self
+                    .preview_image
+                    .select_file(ItemInfoMessage::PreviewImageBrowseDone);
             }
             ItemInfoMessage::BrowseTargetFolder => {
-                self.target_folder.select_dir();
+                return self
+                    .target_folder
+                    .select_dir(ItemInfoMessage::TargetFolderBrowseDone);
+            }
+            ItemInfoMessage::PreviewImageBrowseDone => {
+                self.preview_image.finish_browsing();
+                return self.check_paths();
+            }
+            ItemInfoMessage::TargetFolderBrowseDone => {
+                self.target_folder.finish_browsing();
+                return self.check_paths();
+            }
+            ItemInfoMessage::CopyPreviewImagePath => {
+                return iced::clipboard::write(self.preview_image.resolved_path());
+            }
+            ItemInfoMessage::CopyTargetFolderPath => {
+                return iced::clipboard::write(self.target_folder.resolved_path());
+            }
+            ItemInfoMessage::OpenTargetFolder => self.target_folder.open(),
+            ItemInfoMessage::EditChangeNotesLine(index, new_line) => {
+                let mut lines = self.change_notes_lines();
+                if index < lines.len() {
+                    lines[index] = new_line.replace(['\r', '\n'], " ");
+                    self.change_notes = lines.join("\n");
+                }
+            }
+            ItemInfoMessage::InsertChangeNotesLine(index) => {
+                let mut lines = self.change_notes_lines();
+                if index < lines.len() {
+                    lines.insert(index + 1, String::new());
+                    self.change_notes = lines.join("\n");
+                    return text_input::focus(change_notes_line_id(index + 1));
+                }
+            }
+            ItemInfoMessage::RemoveChangeNotesLine(index) => {
+                let mut lines = self.change_notes_lines();
+                if index < lines.len() && lines.len() > 1 {
+                    lines.remove(index);
+                    self.change_notes = lines.join("\n");
+                }
+            }
+            ItemInfoMessage::EditDescription(new_description) => self.description = new_description,
+            ItemInfoMessage::SetVisibility(visibility) => self.visibility = visibility,
+            ItemInfoMessage::EditTagInput(new_tag) => self.tag_input = new_tag,
+            ItemInfoMessage::AddTag => {
+                if validate_new_tag(&self.tags, &self.tag_input).is_ok() {
+                    self.tags.push(self.tag_input.trim().to_string());
+                    self.tag_input.clear();
+                }
             }
-            ItemInfoMessage::EditChangeNotes(new_notes) => self.change_notes = new_notes,
+            ItemInfoMessage::RemoveTag(index) => {
+                if index < self.tags.len() {
+                    self.tags.remove(index);
+                }
+            }
+            ItemInfoMessage::ResetName => {
+                if let Some(original) = &self.original {
+                    self.name = original.name.clone();
+                }
+            }
+            ItemInfoMessage::ResetDescription => {
+                if let Some(original) = &self.original {
+                    self.description = original.description.clone();
+                }
+            }
+            ItemInfoMessage::ResetTags => {
+                if let Some(original) = &self.original {
+                    self.tags = original.tags.clone();
+                }
+            }
+            ItemInfoMessage::SetCropPreview(crop) => self.crop_preview = crop,
+            ItemInfoMessage::SetPrefixVersion(prefix) => self.prefix_version = prefix,
+            ItemInfoMessage::EditVersion(new_version) => {
+                self.version = new_version;
+                return self.debounce(Field::Version);
+            }
+            ItemInfoMessage::PathsChecked(checks) => self.apply_path_checks(checks),
+            ItemInfoMessage::EditSettled(serial) if serial == self.edit_serial => {
+                self.editing = None;
+                if self.preview_checks().is_none() || self.folder_checks().is_none() {
+                    return self.check_paths();
+                }
+            }
+            ItemInfoMessage::EditSettled(_) => (),
+            ItemInfoMessage::SetMetadataOnly(metadata_only) => self.metadata_only = metadata_only,
         }
+
+        Command::none()
     }
 
-    pub fn view(&self, file_id: Option<PublishedFileId>) -> Element<ItemInfoMessage> {
+    pub fn view(&self, file_id: Option<PublishedFileId>) -> Element<'_, ItemInfoMessage> {
+        let errors = self.field_errors(file_id);
+
         column![
             if let Some(file_id) = file_id {
                 text(format!("Updating item with ID: {}", file_id.0))
             } else {
                 text("Creating new item:")
             },
-            text_input("Name", &self.name, ItemInfoMessage::EditName,),
+            self.current_item_view(),
+            reset_row(
+                validated_text_input(
+                    "Name",
+                    &self.name,
+                    |name| match self.editing {
+                        Some(Field::Name) => Ok(()),
+                        _ => validate_name(name),
+                    },
+                    ItemInfoMessage::EditName,
+                    None,
+                ),
+                self.original
+                    .as_ref()
+                    .map(|original| normalize_name(&original.name) != normalize_name(&self.name)),
+                ItemInfoMessage::ResetName,
+            ),
             self.preview_image.view(
-                "Preview Image",
+                if file_id.is_some() {
+                    "Preview Image"
+                } else {
+                    "Preview Image (required)"
+                },
                 if file_id.is_some() { "Optional" } else { "" },
                 ItemInfoMessage::EditPreviewImage,
                 ItemInfoMessage::BrowsePreviewImage,
+                ItemInfoMessage::CopyPreviewImagePath,
+                None,
             ),
+            match self.settled_error(&errors, Field::PreviewImage) {
+                Some(error) => field_error(error),
+                None if file_id.is_some() && self.preview_image.path.as_os_str().is_empty() => {
+                    text("Leave empty to keep the current preview image.").size(14)
+                }
+                None => text(""),
+            },
+            self.preview_thumbnail_view(),
+            self.crop_preview_view(),
             self.target_folder.view(
                 "Target Folder",
                 "",
                 ItemInfoMessage::EditTargetFolder,
                 ItemInfoMessage::BrowseTargetFolder,
+                ItemInfoMessage::CopyTargetFolderPath,
+                self.folder_checks()
+                    .filter(|checks| checks.folder_is_dir)
+                    .map(|_| ItemInfoMessage::OpenTargetFolder),
             ),
-            text_input(
-                "Changenotes",
-                &self.change_notes,
-                ItemInfoMessage::EditChangeNotes
-            )
+            self.settled_error(&errors, Field::TargetFolder)
+                .map_or(text(""), field_error),
+            self.metadata_only_view(file_id),
+            self.advanced_view(file_id),
+            self.settled_error(&errors, Field::Tags)
+                .map_or(text(""), field_error),
+            self.change_notes_view(),
+            self.version_view(),
+            self.settled_error(&errors, Field::Version)
+                .map_or(text(""), field_error),
         ]
         .into()
     }
+
+    fn advanced_view(&self, file_id: Option<PublishedFileId>) -> Element<'_, ItemInfoMessage> {
+        if !self.show_advanced {
+            return button("\u{25B8} Advanced options")
+                .on_press(ItemInfoMessage::ToggleAdvanced)
+                .into();
+        }
+
+        column![
+            button("\u{25BE} Advanced options").on_press(ItemInfoMessage::ToggleAdvanced),
+            text("Description"),
+            reset_row(
+                text_input(
+                    "Description",
+                    &self.description,
+                    ItemInfoMessage::EditDescription
+                )
+                .into(),
+                self.original
+                    .as_ref()
+                    .map(|original| original.description != self.description),
+                ItemInfoMessage::ResetDescription,
+            ),
+            row![
+                text("Visibility "),
+                pick_list(
+                    if file_id.is_some() {
+                        &Visibility::ALL[..]
+                    } else {
+                        &Visibility::ALL[1..]
+                    },
+                    Some(self.visibility),
+                    ItemInfoMessage::SetVisibility,
+                ),
+            ],
+            self.tags_view(),
+        ]
+        .into()
+    }
+
+    fn settled_error<'a>(&self, errors: &'a FieldErrors, field: Field) -> Option<&'a str> {
+        if self.editing == Some(field) {
+            return None;
+        }
+        errors.get(field)
+    }
+
+    fn change_notes_lines(&self) -> Vec<String> {
+        self.change_notes.split('\n').map(String::from).collect()
+    }
+
+    fn change_notes_view(&self) -> Element<'_, ItemInfoMessage> {
+        let line_count = self.change_notes.split('\n').count();
+        let mut res = column![text("Changenotes (Enter starts a new line)")];
+
+        for (index, line) in self.change_notes.split('\n').enumerate() {
+            let mut remove_button = button("x");
+            if line_count > 1 {
+                remove_button =
+                    remove_button.on_press(ItemInfoMessage::RemoveChangeNotesLine(index));
+            }
+
+            res = res.push(row![
+                text_input("Changenotes", line, move |new_line| {
+                    ItemInfoMessage::EditChangeNotesLine(index, new_line)
+                })
+                .id(change_notes_line_id(index))
+                .on_submit(ItemInfoMessage::InsertChangeNotesLine(index)),
+                remove_button,
+            ]);
+        }
+
+        res.into()
+    }
+
+    fn tags_view(&self) -> Element<'_, ItemInfoMessage> {
+        let can_add = validate_new_tag(&self.tags, &self.tag_input).is_ok();
+
+        let mut add_button = button("Add");
+        let mut input = text_input("Tag", &self.tag_input, ItemInfoMessage::EditTagInput);
+        if can_add {
+            add_button = add_button.on_press(ItemInfoMessage::AddTag);
+            input = input.on_submit(ItemInfoMessage::AddTag);
+        }
+
+        let mut chips = row![];
+        for (index, tag) in self.tags.iter().enumerate() {
+            chips = chips.push(
+                button(text(format!("{} \u{2715}", tag)).size(14))
+                    .on_press(ItemInfoMessage::RemoveTag(index)),
+            );
+        }
+
+        column![
+            reset_row(
+                text("Tags").into(),
+                self.original
+                    .as_ref()
+                    .map(|original| original.tags != self.tags),
+                ItemInfoMessage::ResetTags,
+            ),
+            row![input, add_button],
+            if self.tags.is_empty()
+                && self
+                    .original
+                    .as_ref()
+                    .is_some_and(|original| !original.tags.is_empty())
+            {
+                text("All tags will be removed from the item.").size(14)
+            } else if self.tags.is_empty() {
+                text("No tags.").size(14)
+            } else {
+                text("")
+            },
+            chips,
+        ]
+        .into()
+    }
+
+    fn version_view(&self) -> Element<'_, ItemInfoMessage> {
+        let toggle = checkbox(
+            "Prefix change notes with a version",
+            self.prefix_version,
+            ItemInfoMessage::SetPrefixVersion,
+        );
+
+        if self.prefix_version {
+            column![
+                toggle,
+                text_input(
+                    "Version, e.g. 1.2.3",
+                    &self.version,
+                    ItemInfoMessage::EditVersion
+                ),
+            ]
+            .into()
+        } else {
+            toggle.into()
+        }
+    }
+
+    fn current_item_view(&self) -> Element<'_, ItemInfoMessage> {
+        const DESCRIPTION_PREVIEW_CHARS: usize = 300;
+
+        let original = match &self.original {
+            Some(original) => original,
+            None => return column![].into(),
+        };
+
+        let mut res = column![text("Currently on the Workshop:").size(14)];
+        if let Some(CurrentPreview(handle)) = &self.current_preview {
+            res = res.push(
+                image(handle.clone())
+                    .width(Length::Units(THUMBNAIL_SIZE))
+                    .height(Length::Units(THUMBNAIL_SIZE)),
+            );
+        }
+
+        let mut description: String = original
+            .description
+            .chars()
+            .take(DESCRIPTION_PREVIEW_CHARS)
+            .collect();
+        if description.len() < original.description.len() {
+            description.push_str("...");
+        }
+        if description.is_empty() {
+            description = "(No description)".to_string();
+        }
+        res.push(text(description).size(14)).into()
+    }
+
+    fn preview_thumbnail_view(&self) -> Element<'_, ItemInfoMessage> {
+        if self.preview_size().is_none() {
+            return column![].into();
+        }
+
+        image(image::Handle::from_path(&self.preview_image.path))
+            .width(Length::Units(THUMBNAIL_SIZE))
+            .height(Length::Units(THUMBNAIL_SIZE))
+            .into()
+    }
+
+    fn metadata_only_view(&self, file_id: Option<PublishedFileId>) -> Element<'_, ItemInfoMessage> {
+        if file_id.is_none() {
+            return column![].into();
+        }
+
+        checkbox(
+            "Update details only (don't re-upload the folder)",
+            self.metadata_only,
+            ItemInfoMessage::SetMetadataOnly,
+        )
+        .into()
+    }
+
+    fn crop_preview_view(&self) -> Element<'_, ItemInfoMessage> {
+        match self.non_square_preview() {
+            Some((width, height)) => column![
+                text(format!(
+                    "Preview is {}x{}. Steam shows square previews best.",
+                    width, height
+                ))
+                .size(14),
+                checkbox(
+                    "Crop to a centered square for this upload",
+                    self.crop_preview,
+                    ItemInfoMessage::SetCropPreview,
+                ),
+            ]
+            .into(),
+            _ => column![].into(),
+        }
+    }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+fn reset_row<'a>(
+    field: Element<'a, ItemInfoMessage>,
+    changed: Option<bool>,
+    reset_msg: ItemInfoMessage,
+) -> Element<'a, ItemInfoMessage> {
+    match changed {
+        Some(changed) => {
+            let mut reset_button = button("\u{21BA}");
+            if changed {
+                reset_button = reset_button.on_press(reset_msg);
+            }
+            row![field, reset_button].into()
+        }
+        None => field,
+    }
+}
+
+pub fn dropped_path_message(path: &Path) -> Option<ItemInfoMessage> {
+    let new_path = path.to_string_lossy().into_owned();
+
+    if path.is_dir() {
+        return Some(ItemInfoMessage::EditTargetFolder(new_path));
+    }
+
+    let extension = path
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    if path.is_file() && PREVIEW_EXTENSIONS.contains(&extension.as_str()) {
+        Some(ItemInfoMessage::EditPreviewImage(new_path))
+    } else {
+        None
+    }
+}
+
+fn validate_preview_path(path: &Path) -> Result<(), String> {
+    if !path.exists() {
+        return Err(format!(
+            "Preview image \"{}\" does not exist.",
+            path.to_string_lossy()
+        ));
+    }
+
+    if !path.is_file() {
+        return Err(format!(
+            "Preview image \"{}\" is not a file.",
+            path.to_string_lossy()
+        ));
+    }
+
+    let size = path.metadata().map(|metadata| metadata.len()).unwrap_or(0);
+    if size > preview::MAX_PREVIEW_BYTES {
+        return Err(format!(
+            "Preview image is {}; Steam's limit is {}.",
+            format_bytes(size),
+            format_bytes(preview::MAX_PREVIEW_BYTES)
+        ));
+    }
+
+    let extension = path
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    if !PREVIEW_EXTENSIONS.contains(&extension.as_str()) {
+        return Err(format!(
+            "Preview image \"{}\" must be a JPG, PNG or GIF file.",
+            path.to_string_lossy()
+        ));
+    }
+
+    if !preview::has_image_signature(path) {
+        return Err("Preview image doesn't look like a valid JPG/PNG/GIF.".to_string());
+    }
+
+    Ok(())
+}
+
+fn validate_new_tag(tags: &[String], tag: &str) -> Result<(), String> {
+    let tag = tag.trim();
+    if tag.is_empty() {
+        return Err("Tags cannot be empty.".to_string());
+    }
+
+    if tags
+        .iter()
+        .any(|existing| existing.eq_ignore_ascii_case(tag))
+    {
+        return Err(format!("Tag \"{}\" is listed twice.", tag));
+    }
+
+    Ok(())
+}
+
+fn field_error<'a>(error: &str) -> iced::widget::Text<'a> {
+    text(error).size(14).style(ERROR_COLOR)
+}
+
+fn change_notes_line_id(index: usize) -> text_input::Id {
+    text_input::Id::new(format!("change_notes_{}", index))
+}
+
+pub fn normalize_name(name: &str) -> String {
+    name.trim().nfc().collect()
+}
+
+fn validate_name(name: &str) -> Result<(), String> {
+    let length = normalize_name(name).len();
+    if length == 0 {
+        return Err("Name cannot be empty.".to_string());
+    }
+
+    if length > MAX_NAME_BYTES {
+        return Err(format!(
+            "Name must be {} bytes or fewer (currently {}).",
+            MAX_NAME_BYTES, length
+        ));
+    }
+
+    Ok(())
+}
+
+pub fn skeleton_view<'a>(file_id: PublishedFileId) -> Element<'a, ItemInfoMessage> {
+    column![
+        text(format!("Loading item with ID: {}...", file_id.0)),
+        text("Busy: waiting for Steam. Fields unlock once the item is loaded."),
+        text_input("Loading name...", "", ItemInfoMessage::EditName),
+        column![
+            text("Preview Image"),
+            row![
+                text_input("Loading...", "", ItemInfoMessage::EditPreviewImage),
+                button("Browse"),
+            ],
+        ],
+        column![
+            text("Target Folder"),
+            row![
+                text_input("", "", ItemInfoMessage::EditTargetFolder),
+                button("Browse"),
+            ],
+        ],
+        text("Description"),
+        text_input("Loading...", "", ItemInfoMessage::EditDescription),
+        text_input("Changenotes", "", |line| {
+            ItemInfoMessage::EditChangeNotesLine(0, line)
+        }),
+    ]
+    .into()
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Visibility {
+    #[default]
+    KeepCurrent,
+    Public,
+    FriendsOnly,
+    Private,
+    Unlisted,
+}
+
+impl Visibility {
+    const ALL: [Visibility; 5] = [
+        Visibility::KeepCurrent,
+        Visibility::Public,
+        Visibility::FriendsOnly,
+        Visibility::Private,
+        Visibility::Unlisted,
+    ];
+
+    pub fn steam_visibility(self) -> Option<PublishedFileVisibility> {
+        match self {
+            Visibility::KeepCurrent => None,
+            Visibility::Public => Some(PublishedFileVisibility::Public),
+            Visibility::FriendsOnly => Some(PublishedFileVisibility::FriendsOnly),
+            Visibility::Private => Some(PublishedFileVisibility::Private),
+            Visibility::Unlisted => Some(PublishedFileVisibility::Unlisted),
+        }
+    }
+}
+
+impl Visibility {
+    pub fn hidden_notice(self) -> Option<&'static str> {
+        match self {
+            Visibility::KeepCurrent | Visibility::Public => None,
+            Visibility::FriendsOnly => Some("This item is friends-only, so the link won't work for anyone else. Choose \"Public\" as the visibility on a later update, or change the visibility on the item's page, to share it."),
+            Visibility::Private => Some("This item is private, so the link only works for you. Choose \"Public\" as the visibility on a later update, or change the visibility on the item's page, to share it."),
+            Visibility::Unlisted => Some("This item is unlisted. Anyone with the link can open it, but it won't show up in Workshop searches until you make it public."),
+        }
+    }
+}
+
+impl fmt::Display for Visibility {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Visibility::KeepCurrent => write!(f, "Keep current"),
+            Visibility::Public => write!(f, "Public"),
+            Visibility::FriendsOnly => write!(f, "Friends only"),
+            Visibility::Private => write!(f, "Private"),
+            Visibility::Unlisted => write!(f, "Unlisted"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ItemInfo {
     pub name: String,
     pub preview_image: PathBuf,
     pub target_folder: PathBuf,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub visibility: Visibility,
+    #[serde(default)]
+    pub tags: Vec<String>,
     pub change_notes: String,
+    #[serde(default)]
+    pub crop_preview: bool,
+    #[serde(default)]
+    pub version: Option<String>,
+    #[serde(skip)]
+    pub preview_url: Option<String>,
+    #[serde(skip)]
+    pub metadata_only: bool,
+    #[serde(skip)]
+    pub loaded_description: Option<String>,
+    #[serde(skip)]
+    pub loaded_tags: Option<Vec<String>>,
+}
+
+impl ItemInfo {
+    pub fn full_change_notes(&self) -> String {
+        match &self.version {
+            Some(version) if self.change_notes.is_empty() => format!("v{}", version),
+            Some(version) => format!("v{} \u{2014} {}", version, self.change_notes),
+            None => self.change_notes.clone(),
+        }
+    }
 }
 
 impl From<ItemInfo> for ItemInfoState {
@@ -96,8 +1077,22 @@ impl From<ItemInfo> for ItemInfoState {
         ItemInfoState {
             name: value.name,
             preview_image: FileField::from(value.preview_image),
+            path_checks: None,
+            edit_serial: 0,
+            editing: None,
+            crop_preview: value.crop_preview,
             target_folder: FileField::from(value.target_folder),
+            metadata_only: value.metadata_only,
+            description: value.description,
+            visibility: value.visibility,
+            tags: value.tags,
+            tag_input: String::new(),
             change_notes: value.change_notes,
+            prefix_version: value.version.is_some(),
+            version: value.version.unwrap_or_default(),
+            original: None,
+            current_preview: None,
+            show_advanced: false,
         }
     }
 }
@@ -106,55 +1101,113 @@ impl From<QueryResult> for ItemInfo {
     fn from(value: QueryResult) -> Self {
         ItemInfo {
             name: value.title,
-            preview_image: PathBuf::new(),
-            target_folder: PathBuf::new(),
-            change_notes: String::new(),
+            description: value.description,
+            tags: value.tags,
+            ..Default::default()
         }
     }
 }
 
 impl TryFrom<ItemInfoState> for ItemInfo {
-    type Error = String;
+    type Error = FieldErrors;
 
     fn try_from(value: ItemInfoState) -> Result<Self, Self::Error> {
-        if value.name.is_empty() {
-            return Err("Name cannot be empty.".to_string());
-        }
-
-        let preview_field_exists = value.preview_image.path.exists();
-        let has_preview = preview_field_exists && value.preview_image.path.is_file();
-        if !has_preview {
-            if !value.preview_image.path.to_string_lossy().is_empty() {
-                if !preview_field_exists {
-                    return Err(format!(
-                        "Preview image \"{}\" does not exist.",
-                        value.preview_image.path.to_string_lossy()
-                    ));
-                } else {
-                    return Err(format!(
-                        "Preview image \"{}\" is not a file.",
-                        value.preview_image.path.to_string_lossy()
-                    ));
-                }
-            }
+        let mut errors = FieldErrors::default();
+
+        errors.check(Field::Name, validate_name(&value.name));
+
+        for (index, tag) in value.tags.iter().enumerate() {
+            errors.check(Field::Tags, validate_new_tag(&value.tags[..index], tag));
         }
 
-        if !value.target_folder.path.exists() {
-            if value.target_folder.path.to_string_lossy().is_empty() {
-                return Err("Target folder cannot be empty.".to_string());
-            } else {
-                return Err(format!(
-                    "Target folder \"{}\" does not exist.",
-                    value.target_folder.path.to_string_lossy()
-                ));
-            }
+        if value.prefix_version && value.version.trim().is_empty() {
+            errors.check(
+                Field::Version,
+                Err("Enter a version or turn off the version prefix.".to_string()),
+            );
+        }
+
+        errors.check(Field::PreviewImage, value.preview_result());
+        if !value.metadata_only {
+            errors.check(Field::TargetFolder, value.folder_result());
+        }
+
+        if !errors.is_empty() {
+            return Err(errors);
         }
 
         Ok(ItemInfo {
-            name: value.name,
+            name: normalize_name(&value.name),
             preview_image: value.preview_image.path,
             target_folder: value.target_folder.path,
+            metadata_only: value.metadata_only,
+            description: value.description,
+            visibility: value.visibility,
+            tags: value.tags,
             change_notes: value.change_notes,
+            crop_preview: value.crop_preview,
+            version: value
+                .prefix_version
+                .then(|| value.version.trim().to_string()),
+            preview_url: None,
+            loaded_description: value
+                .original
+                .as_ref()
+                .map(|original| original.description.clone()),
+            loaded_tags: value.original.map(|original| original.tags),
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_name_matches_composed_and_decomposed_forms() {
+        let composed = "caf\u{e9}";
+        let decomposed = "cafe\u{301}";
+        assert_ne!(composed, decomposed);
+        assert_eq!(normalize_name(composed), normalize_name(decomposed));
+        assert_eq!(normalize_name(&format!(" {} ", decomposed)), composed);
+    }
+
+    #[test]
+    fn reset_restores_original_description_and_tags() {
+        let original = ItemInfo {
+            name: "Item".to_string(),
+            description: "Original description".to_string(),
+            tags: vec!["Mod".to_string()],
+            ..Default::default()
+        };
+        let mut state = ItemInfoState::from_original(original);
+
+        let _ = state.update(ItemInfoMessage::EditDescription(String::new()));
+        let _ = state.update(ItemInfoMessage::RemoveTag(0));
+        assert!(state.description.is_empty());
+        assert!(state.tags.is_empty());
+
+        let _ = state.update(ItemInfoMessage::ResetDescription);
+        let _ = state.update(ItemInfoMessage::ResetTags);
+        assert_eq!(state.description, "Original description");
+        assert_eq!(state.tags, vec!["Mod".to_string()]);
+    }
+
+    #[test]
+    fn advanced_options_start_collapsed_and_are_not_an_edit() {
+        let start = ItemInfoState::default();
+        let mut state = start.clone();
+        assert!(!state.show_advanced);
+
+        let _ = state.update(ItemInfoMessage::ToggleAdvanced);
+        assert!(state.show_advanced);
+        assert!(!state.has_edits_since(&start));
+
+        state.load_preset(ItemInfo {
+            name: "Preset".to_string(),
+            visibility: Visibility::Public,
+            ..Default::default()
+        });
+        assert!(state.show_advanced);
+    }
+}
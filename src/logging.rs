@@ -0,0 +1,46 @@
+use simplelog::{
+    ColorChoice, CombinedLogger, ConfigBuilder, LevelFilter, SharedLogger, TermLogger,
+    TerminalMode, WriteLogger,
+};
+use std::fs::OpenOptions;
+use std::path::{Path, PathBuf};
+
+const LOG_FILE_NAME: &str = "4wu.log";
+const MAX_LOG_BYTES: u64 = 1024 * 1024;
+
+fn log_path() -> Option<PathBuf> {
+    let exe = std::env::current_exe().ok()?;
+    exe.parent().map(|dir| dir.join(LOG_FILE_NAME))
+}
+
+fn rotate(path: &Path) {
+    let too_large = std::fs::metadata(path)
+        .map(|metadata| metadata.len() > MAX_LOG_BYTES)
+        .unwrap_or(false);
+
+    if too_large {
+        let _ = std::fs::rename(path, path.with_extension("log.old"));
+    }
+}
+
+pub fn init() {
+    let config = ConfigBuilder::new().set_time_format_rfc3339().build();
+
+    // Everything goes to stderr so stdout stays clean for command-line output like --json.
+    let mut loggers: Vec<Box<dyn SharedLogger>> = vec![TermLogger::new(
+        LevelFilter::Info,
+        config.clone(),
+        TerminalMode::Stderr,
+        ColorChoice::Auto,
+    )];
+
+    if let Some(path) = log_path() {
+        rotate(&path);
+        match OpenOptions::new().create(true).append(true).open(&path) {
+            Ok(file) => loggers.push(WriteLogger::new(LevelFilter::Debug, config, file)),
+            Err(error) => eprintln!("Failed to open log file {:?}: {}", path, error),
+        }
+    }
+
+    let _ = CombinedLogger::init(loggers);
+}
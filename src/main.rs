@@ -1,64 +1,298 @@
+mod cli;
+mod config;
 mod err_dialog_types;
 mod file_field;
 mod item_info;
+mod logging;
+mod manifest;
 mod my_steamworks;
-use err_dialog_types::ErrorDialogUnwrapper;
-use iced::widget::{button, column, row, text, text_input};
-use iced::{Application, Command, Element, Settings};
-use item_info::{ItemInfo, ItemInfoMessage, ItemInfoState};
-use my_steamworks::WorkshopClient;
-use std::num::IntErrorKind;
-use steamworks::{AppId, PublishedFileId, SteamError};
+mod preview;
+mod profiles;
+mod recent;
+mod storage;
+mod theme;
+mod toasts;
+mod validated_input;
+mod versions;
+use config::Config;
+use err_dialog_types::{confirm_dialog, error_dialog, FriendlyMessage};
+use iced::widget::{button, checkbox, column, pick_list, progress_bar, row, text};
+use iced::{event, keyboard, window, Application, Command, Element, Event, Settings, Subscription};
+use item_info::{ItemInfo, ItemInfoMessage, ItemInfoState, Visibility};
+use manifest::{format_bytes, ExcludeFilter, FolderScan};
+use my_steamworks::{UploadError, WorkshopClient};
+use native_dialog::FileDialog;
+use profiles::{Profile, Profiles};
+use recent::{RecentItem, RecentItems};
+use std::future::Future;
+use std::num::{IntErrorKind, ParseIntError};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use steamworks::{AppId, PublishedFileId, SteamError, UpdateStatus};
+use theme::ThemeChoice;
+use toasts::Toasts;
+use validated_input::{validated_text_input, ERROR_COLOR};
+use versions::VersionHistory;
 
-const APP_ID_STR: &str = include_str!("../steam_appid.txt");
+const APP_ID_STR: &str = include_str!(concat!(env!("OUT_DIR"), "/steam_appid.txt"));
+const ICON_PNG: &[u8] = include_bytes!("../assets/icon.png");
+const LARGE_FOLDER_BYTES: u64 = 1024 * 1024 * 1024;
+const SPINNER_FRAMES: [char; 4] = ['|', '/', '-', '\\'];
+const SPINNER_INTERVAL: Duration = Duration::from_millis(150);
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Message {
     SetExistingId(String),
     EditItemData(ItemInfoMessage),
-    ReceiveFoundItemInfo(ItemInfo),
-    ReceiveItemId(PublishedFileId),
+    ReceiveFoundItemInfo(PublishedFileId, ItemInfo, Option<AppId>),
+    ReceiveSearchError(PublishedFileId, SteamError),
+    ReceiveItemId(PublishedFileId, bool),
     ReceiveSteamError(SteamError),
+    ReceiveUploadDone(u64, PublishedFileId, bool),
+    ReceiveUploadError(u64, UploadError),
+    ReceiveFolderScan(PathBuf, Option<FolderScan>),
+    ManifestSaved,
+    ReceiveCurrentPreview(PublishedFileId, Option<Vec<u8>>),
     Proceed,
+    Retry,
     GoBack,
     TermsLinkPressed,
+    SetShowErrorDetails(bool),
+    SaveProfile,
+    AddToBatch,
+    TestUpload,
+    Validate,
+    StartBatch,
+    ClearBatch,
+    PauseBatch,
+    ReceiveBatchItemId(usize, Result<(PublishedFileId, bool), SteamError>),
+    ReceiveBatchResult(usize, Result<PublishedFileId, UploadError>),
+    UpdatePreviewOnly,
+    DeleteItem,
+    ReceiveDeleteDone(PublishedFileId),
+    CancelUpload,
+    CopyDebugInfo,
+    CopyItemLink,
+    RefreshItem,
+    SavePreset,
+    ResumePendingUpload,
+    DiscardPendingUpload,
+    LoadPreset,
+    KeyPressed(keyboard::KeyCode),
+    ToggleTheme,
+    ReceiveSystemTheme(ThemeChoice),
+    LoadProfile(String),
+    ReopenLastUpload,
+    SelectRecentItem(RecentItem),
+    ToggleFolderContents,
+    SetExcludeHiddenFiles(bool),
+    FileDropped(PathBuf),
+    WindowResized(u32, u32),
+    WindowMoved(i32, i32),
+    CloseRequested,
+    Tick(Instant),
 }
 
 impl Message {
     fn receive_item_id(res: Result<(PublishedFileId, bool), SteamError>) -> Self {
         match res {
-            Ok((id, _)) => Message::ReceiveItemId(id),
+            Ok((id, needs_legal_agreement)) => Message::ReceiveItemId(id, needs_legal_agreement),
             Err(err) => Message::ReceiveSteamError(err),
         }
     }
 
-    fn receive_item_info(res: Result<ItemInfo, SteamError>) -> Self {
+    fn receive_upload_result(
+        upload_serial: u64,
+        res: Result<(PublishedFileId, bool), UploadError>,
+    ) -> Self {
         match res {
-            Ok(item_info) => Message::ReceiveFoundItemInfo(item_info),
-            Err(err) => Message::ReceiveSteamError(err),
+            Ok((id, needs_legal_agreement)) => {
+                Message::ReceiveUploadDone(upload_serial, id, needs_legal_agreement)
+            }
+            Err(err) => Message::ReceiveUploadError(upload_serial, err),
+        }
+    }
+
+    fn receive_item_info(
+        item_id: PublishedFileId,
+        res: Result<(ItemInfo, Option<AppId>), SteamError>,
+    ) -> Self {
+        match res {
+            Ok((item_info, always_allow)) => {
+                Message::ReceiveFoundItemInfo(item_id, item_info, always_allow)
+            }
+            Err(err) => Message::ReceiveSearchError(item_id, err),
         }
     }
 }
 
+type BatchEntry = (Option<PublishedFileId>, ItemInfo);
+
 #[derive(Clone, PartialEq, Eq)]
 enum ModelState {
     Initial(String),
     ExistingIdSearching(PublishedFileId, Option<SteamError>),
     ItemForm(Option<PublishedFileId>, ItemInfoState),
+    SteamOffline(Option<PublishedFileId>, ItemInfo),
     CreatingItem(ItemInfo),
     CreationError(ItemInfo, SteamError),
     SendingItem(PublishedFileId, ItemInfo),
-    SendingError(PublishedFileId, ItemInfo, SteamError),
-    Done(PublishedFileId),
+    SendingError(PublishedFileId, ItemInfo, UploadError),
+    SendingPreview(PublishedFileId, ItemInfoState),
+    PreviewError(PublishedFileId, ItemInfoState, UploadError),
+    SteamLost(Box<ModelState>),
+    Cancelled(PublishedFileId, ItemInfo),
+    BatchUploading(Vec<BatchEntry>, Vec<Result<PublishedFileId, UploadError>>),
+    BatchDone(Vec<BatchEntry>, Vec<Result<PublishedFileId, UploadError>>),
+    Deleting(PublishedFileId, ItemInfoState),
+    DeletionError(PublishedFileId, ItemInfoState, SteamError),
+    Done(PublishedFileId, Visibility),
 }
 
 struct Model {
-    client: WorkshopClient,
+    client: Option<WorkshopClient>,
+    app_id: AppId,
+    persona_name: String,
     state: ModelState,
+    folder_scan: Option<FolderScan>,
+    scan_in_progress: bool,
+    config: Config,
+    system_theme: ThemeChoice,
+    profiles: Profiles,
+    versions: VersionHistory,
+    recent_items: RecentItems,
+    batch: Vec<BatchEntry>,
+    batch_pause_requested: bool,
+    test_upload: Option<ItemInfo>,
+    form_start: Option<ItemInfoState>,
+    validation_report: Option<Vec<String>>,
+    should_exit: bool,
+    last_upload: Option<Profile>,
+    pending_upload: Option<Profile>,
+    show_folder_contents: bool,
+    legal_agreement_accepted: Option<bool>,
+    exclude_hidden_files: bool,
+    staged_paths: Vec<PathBuf>,
+    abandoned_paths: Vec<(u64, Vec<PathBuf>)>,
+    busy_since: Instant,
+    now: Instant,
+    upload_progress: Option<(UpdateStatus, u64, u64)>,
+    upload_serial: u64,
+    toasts: Toasts,
+}
+
+fn save_batch_queue(queue: &[BatchEntry]) {
+    let queue: Vec<Profile> = queue
+        .iter()
+        .map(|(item_id, item_info)| Profile::queued(*item_id, item_info.clone()))
+        .collect();
+    Profile::save_batch_queue(&queue);
 }
 
-fn initial_view<'a>(existing_id: &str) -> Element<'a, Message> {
-    let item_id = existing_id.parse::<u64>().map(PublishedFileId);
+fn normalize_item_id(input: &str) -> &str {
+    let input = input.trim();
+
+    let id_param = input.split_once('?').and_then(|(_, query)| {
+        query
+            .split(['&', '#'])
+            .find_map(|pair| pair.strip_prefix("id="))
+    });
+
+    let extracted = if let Some(value) = id_param {
+        value
+    } else if input.contains("://") {
+        let path = input.split(['?', '#']).next().unwrap_or(input);
+        path.trim_end_matches('/')
+            .rsplit('/')
+            .next()
+            .unwrap_or(path)
+    } else if input
+        .get(..3)
+        .is_some_and(|p| p.eq_ignore_ascii_case("id:"))
+    {
+        input[3..].trim()
+    } else {
+        input.strip_prefix('#').unwrap_or(input).trim()
+    };
+
+    if extracted.is_empty() {
+        input
+    } else {
+        extracted
+    }
+}
+
+fn parse_item_id(input: &str) -> Result<PublishedFileId, ParseIntError> {
+    normalize_item_id(input).parse::<u64>().map(PublishedFileId)
+}
+
+fn item_url(item_id: PublishedFileId) -> String {
+    format!(
+        "https://steamcommunity.com/sharedfiles/filedetails/?id={}",
+        item_id.0
+    )
+}
+
+fn validate_item_id(existing_id: &str) -> Result<(), String> {
+    match parse_item_id(existing_id).map_err(|error| *error.kind()) {
+        Ok(_) | Err(IntErrorKind::Empty) => Ok(()),
+        Err(IntErrorKind::PosOverflow | IntErrorKind::NegOverflow) => {
+            Err("Item ID is too large.".to_string())
+        }
+        Err(IntErrorKind::InvalidDigit) => Err("Item ID must be digits only.".to_string()),
+        Err(kind) => Err(format!("Invalid item ID: {:?}.", kind)),
+    }
+}
+
+fn load_preset() -> Option<Profile> {
+    let result = FileDialog::new()
+        .add_filter("Preset", &["json"])
+        .show_open_single_file();
+
+    let path = match result {
+        Ok(path) => path?,
+        Err(err) => {
+            error_dialog(format!("Failed to choose a preset file. Error: {:?}", err).as_str());
+            return None;
+        }
+    };
+
+    match Profile::import(&path) {
+        Ok(profile) => Some(profile),
+        Err(error) => {
+            error_dialog(&error);
+            None
+        }
+    }
+}
+
+fn window_event(event: Event, status: event::Status) -> Option<Message> {
+    match event {
+        Event::Window(window::Event::FileDropped(path)) => Some(Message::FileDropped(path)),
+        Event::Window(window::Event::Resized { width, height }) => {
+            Some(Message::WindowResized(width, height))
+        }
+        Event::Window(window::Event::Moved { x, y }) => Some(Message::WindowMoved(x, y)),
+        Event::Window(window::Event::CloseRequested) => Some(Message::CloseRequested),
+        Event::Keyboard(keyboard::Event::KeyPressed { key_code, .. })
+            if status == event::Status::Ignored =>
+        {
+            Some(Message::KeyPressed(key_code))
+        }
+        _ => None,
+    }
+}
+
+fn initial_view<'a>(
+    existing_id: &str,
+    config: &Config,
+    profiles: &Profiles,
+    last_upload: Option<&Profile>,
+    recent_items: &RecentItems,
+    batch: &[BatchEntry],
+    theme: ThemeChoice,
+) -> Element<'a, Message> {
+    let item_id = parse_item_id(existing_id);
 
     let mut res = column![
         text("4onen's Steam Workshop Uploader"),
@@ -71,24 +305,258 @@ fn initial_view<'a>(existing_id: &str) -> Element<'a, Message> {
         } else {
             button("Update existing").on_press(Message::Proceed)
         },
-        text_input("Existing item ID", existing_id, Message::SetExistingId)
-            .on_submit(Message::Proceed),
+        validated_text_input(
+            "Existing item ID",
+            existing_id,
+            validate_item_id,
+            Message::SetExistingId,
+            Some(Message::Proceed),
+        ),
     ];
 
-    if let Err(error) = item_id {
-        if *error.kind() != IntErrorKind::Empty {
-            res = res.push(text(format!("Invalid item ID: {}.", error)));
-        }
+    if let Some(last_upload) = last_upload {
+        res = res.push(
+            button(text(format!(
+                "Reopen last upload: \"{}\"",
+                last_upload.name
+            )))
+            .on_press(Message::ReopenLastUpload),
+        );
+    }
+
+    if !batch.is_empty() {
+        let plural = if batch.len() == 1 { "" } else { "s" };
+        res = res.push(column![
+            text(format!(
+                "{} item{} queued for batch upload.",
+                batch.len(),
+                plural
+            )),
+            row![
+                button("Upload batch").on_press(Message::StartBatch),
+                button("Clear batch").on_press(Message::ClearBatch),
+            ],
+        ]);
+    }
+
+    if !recent_items.items.is_empty() {
+        res = res.push(
+            pick_list(recent_items.items.clone(), None, Message::SelectRecentItem)
+                .placeholder("Recently uploaded items"),
+        );
+    }
+
+    res = if profiles.profiles.is_empty() {
+        res.push(
+            text("No saved profiles yet. Use \"Save as profile\" on the item form to add one.")
+                .size(14),
+        )
+    } else {
+        res.push(
+            pick_list(profiles.names(), None, Message::LoadProfile)
+                .placeholder("Load a saved profile"),
+        )
+    };
+
+    res.push(row![
+        checkbox(
+            "Show technical error details",
+            config.show_error_details,
+            Message::SetShowErrorDetails,
+        ),
+        button(match theme {
+            ThemeChoice::Light => "Dark theme",
+            ThemeChoice::Dark => "Light theme",
+        })
+        .on_press(Message::ToggleTheme),
+    ])
+    .into()
+}
+
+fn pending_upload_view<'a>(pending: &Profile) -> Element<'a, Message> {
+    column![
+        text(format!(
+            "Item {} (\"{}\") was created but its upload didn't finish.",
+            pending.item_id.unwrap_or_default(),
+            pending.name
+        )),
+        row![
+            button("Resume upload").on_press(Message::ResumePendingUpload),
+            button("Dismiss").on_press(Message::DiscardPendingUpload),
+        ],
+    ]
+    .into()
+}
+
+fn batch_view<'a>(
+    queue: &[BatchEntry],
+    results: &[Result<PublishedFileId, UploadError>],
+) -> Element<'a, Message> {
+    let mut res = column![];
+
+    for (index, (maybe_id, item_info)) in queue.iter().enumerate() {
+        let status = match results.get(index) {
+            Some(Ok(item_id)) => format!("\u{2713} {} ({})", item_info.name, item_id.0),
+            Some(Err(err)) => format!("\u{2717} {}: {}", item_info.name, err),
+            None if index == results.len() => format!("Uploading {}...", item_info.name),
+            None => match maybe_id {
+                Some(item_id) => format!("Waiting: {} ({})", item_info.name, item_id.0),
+                None => format!("Waiting: {} (new)", item_info.name),
+            },
+        };
+        res = res.push(text(status).size(14));
     }
 
     res.into()
 }
 
+fn terms_view<'a>(legal_agreement_accepted: Option<bool>) -> Element<'a, Message> {
+    match legal_agreement_accepted {
+        Some(true) => column![
+            text("\u{2713} Agreement accepted"),
+            button("Terms of Service").on_press(Message::TermsLinkPressed)
+        ],
+        Some(false) => column![
+            text("You still need to accept the Steam workshop legal agreement before your items can be seen by others."),
+            button("Review and accept").on_press(Message::TermsLinkPressed)
+        ],
+        None => column![
+            text("By submitting this item, you agree to the Steam workshop"),
+            button("Terms of Service").on_press(Message::TermsLinkPressed)
+        ],
+    }
+    .into()
+}
+
+fn busy_view<'a>(spinner: char, status: String) -> Element<'a, Message> {
+    column![
+        text(format!("{} {}", spinner, status)),
+        text("Busy: waiting for Steam. Other actions are unavailable until this finishes."),
+    ]
+    .into()
+}
+
+fn upload_progress_view<'a>(progress: Option<(UpdateStatus, u64, u64)>) -> Element<'a, Message> {
+    let (status, processed, total) = match progress {
+        Some(progress) => progress,
+        None => return text("Waiting for Steam...").into(),
+    };
+
+    let status = match status {
+        UpdateStatus::Invalid => "Waiting for Steam",
+        UpdateStatus::PreparingConfig => "Preparing item settings",
+        UpdateStatus::PreparingContent => "Preparing content",
+        UpdateStatus::UploadingContent => "Uploading content",
+        UpdateStatus::UploadingPreviewFile => "Uploading preview image",
+        UpdateStatus::CommittingChanges => "Committing changes",
+    };
+
+    if total == 0 {
+        return text(format!("{}...", status)).into();
+    }
+
+    let percent = processed.min(total) * 100 / total;
+    column![
+        text(format!("{}...", status)),
+        progress_bar(0.0..=total as f32, processed as f32),
+        text(format!(
+            "Uploaded {} of {} ({}%)",
+            format_bytes(processed),
+            format_bytes(total),
+            percent
+        ))
+        .size(14),
+    ]
+    .into()
+}
+
+fn folder_contents_view<'a>(folder_scan: &FolderScan, expanded: bool) -> Element<'a, Message> {
+    const MAX_ENTRIES: usize = 12;
+
+    let manifest = &folder_scan.manifest;
+    let summary = format!(
+        "{} files, {} total",
+        manifest.files.len(),
+        format_bytes(manifest.total_size())
+    );
+
+    if !expanded {
+        return row![
+            text(summary),
+            button("Show files").on_press(Message::ToggleFolderContents)
+        ]
+        .into();
+    }
+
+    let entries = manifest.top_level_entries();
+    let mut res = column![row![
+        text(summary),
+        button("Hide files").on_press(Message::ToggleFolderContents)
+    ]];
+
+    for entry in entries.iter().take(MAX_ENTRIES) {
+        res = res.push(text(if entry.is_dir {
+            format!(
+                "{}/ ({} files, {})",
+                entry.name,
+                entry.file_count,
+                format_bytes(entry.size)
+            )
+        } else {
+            format!("{} ({})", entry.name, format_bytes(entry.size))
+        }));
+    }
+
+    if entries.len() > MAX_ENTRIES {
+        res = res.push(text(format!("+{} more", entries.len() - MAX_ENTRIES)));
+    }
+
+    res.into()
+}
+
+fn list_entries(entries: &[String]) -> String {
+    const MAX_LISTED: usize = 5;
+
+    let listed = entries
+        .iter()
+        .take(MAX_LISTED)
+        .cloned()
+        .collect::<Vec<_>>()
+        .join(", ");
+    if entries.len() > MAX_LISTED {
+        format!("{} and {} more", listed, entries.len() - MAX_LISTED)
+    } else {
+        listed
+    }
+}
+
+fn hidden_files_view<'a>(hidden: &[String], exclude: bool) -> Element<'a, Message> {
+    let mut warning = text(format!("Hidden files found: {}", list_entries(hidden))).size(14);
+    if !exclude {
+        warning = warning.style(ERROR_COLOR);
+    }
+
+    column![
+        warning,
+        checkbox(
+            "Leave hidden files out of uploads this session",
+            exclude,
+            Message::SetExcludeHiddenFiles,
+        ),
+    ]
+    .into()
+}
+
 fn edit_item_view<'a>(
     item_info: &'a ItemInfoState,
     existing_id: Option<PublishedFileId>,
+    folder_scan: Option<&FolderScan>,
+    scan_in_progress: bool,
+    exclude_hidden_files: bool,
+    show_folder_contents: bool,
+    legal_agreement_accepted: Option<bool>,
 ) -> Element<'a, Message> {
-    let ready_info = ItemInfo::try_from(item_info.clone());
+    let ready_info = item_info.validate(existing_id);
 
     let mut fwd_button = if existing_id.is_some() {
         button("Update")
@@ -96,44 +564,868 @@ fn edit_item_view<'a>(
         button("Create")
     };
 
-    if let Ok(_) = &ready_info {
+    let mut save_profile_button = button("Save as profile");
+    let mut save_preset_button = button("Save preset");
+    let mut add_to_batch_button = button("Add to batch");
+    let mut test_upload_button = button("Test upload (creates a hidden item)");
+    let mut validate_button = button("Validate");
+    if !scan_in_progress {
+        validate_button = validate_button.on_press(Message::Validate);
+    }
+
+    let mut preview_only_button = button("Update preview only");
+    if existing_id.is_some() && item_info.validate_preview().is_ok() {
+        preview_only_button = preview_only_button.on_press(Message::UpdatePreviewOnly);
+    }
+
+    let mut delete_button = button("Delete item");
+    let mut refresh_button = button("Refresh");
+    if existing_id.is_some() {
+        delete_button = delete_button.on_press(Message::DeleteItem);
+        refresh_button = refresh_button.on_press(Message::RefreshItem);
+    }
+
+    if ready_info.is_ok() && !scan_in_progress {
         fwd_button = fwd_button.on_press(Message::Proceed);
     }
+    if ready_info.is_ok() {
+        save_profile_button = save_profile_button.on_press(Message::SaveProfile);
+        save_preset_button = save_preset_button.on_press(Message::SavePreset);
+    }
+    if ready_info.is_ok() && !scan_in_progress {
+        add_to_batch_button = add_to_batch_button.on_press(Message::AddToBatch);
+        test_upload_button = test_upload_button.on_press(Message::TestUpload);
+    }
 
     column![
-        item_info
-            .view(existing_id)
-            .map(move |message| Message::EditItemData(message)),
-        column![
-            text("By submitting this item, you agree to the Steam workshop"),
-            button("Terms of Service").on_press(Message::TermsLinkPressed)
+        item_info.view(existing_id).map(Message::EditItemData),
+        terms_view(legal_agreement_accepted),
+        match folder_scan {
+            Some(scan) => folder_contents_view(scan, show_folder_contents),
+            None => text("").into(),
+        },
+        match folder_scan.and_then(|scan| scan.changes.as_ref()) {
+            Some(changes) if changes.is_empty() && !item_info.metadata_only() => text(format!(
+                "{} Tick \"Update details only\" to skip re-uploading it.",
+                changes
+            )),
+            Some(changes) => text(changes),
+            None => text(""),
+        },
+        match folder_scan.filter(|scan| !scan.hidden.is_empty()) {
+            Some(scan) => hidden_files_view(&scan.hidden, exclude_hidden_files),
+            None => text("").into(),
+        },
+        match folder_scan.filter(|scan| !scan.junk.is_empty()) {
+            Some(scan) =>
+                text(format!("Left out of uploads: {}", list_entries(&scan.junk))).size(14),
+            None => text(""),
+        },
+        row![
+            button("Go back").on_press(Message::GoBack),
+            save_profile_button,
+            validate_button,
+            fwd_button
+        ],
+        row![
+            save_preset_button,
+            button("Load preset").on_press(Message::LoadPreset)
         ],
-        row![button("Go back").on_press(Message::GoBack), fwd_button],
+        if existing_id.is_some() {
+            row![
+                add_to_batch_button,
+                preview_only_button,
+                refresh_button,
+                delete_button
+            ]
+        } else {
+            row![add_to_batch_button, test_upload_button]
+        },
         match ready_info {
+            Ok(_) if scan_in_progress => text("Still checking folder..."),
             Ok(_) => text(""),
-            Err(error) => text(format!("{}", error)),
+            Err(errors) if errors.len() == 1 => text("Fix the problem marked above."),
+            Err(errors) => text(format!("Fix the {} problems marked above.", errors.len())),
         },
     ]
     .into()
 }
 
 impl Model {
+    fn resume_state(&self) -> ModelState {
+        match self.state.clone() {
+            ModelState::ExistingIdSearching(item_id, _) => {
+                ModelState::Initial(item_id.0.to_string())
+            }
+            ModelState::CreatingItem(item_info) => ModelState::ItemForm(None, item_info.into()),
+            ModelState::SendingItem(item_id, item_info) => {
+                ModelState::ItemForm(Some(item_id), item_info.into())
+            }
+            ModelState::SendingPreview(item_id, item_info)
+            | ModelState::Deleting(item_id, item_info) => {
+                ModelState::ItemForm(Some(item_id), item_info)
+            }
+            state => state,
+        }
+    }
+
+    fn client(&self) -> &WorkshopClient {
+        self.client
+            .as_ref()
+            .expect("Steam client is only missing while reconnecting")
+    }
+
+    fn reconnect(&mut self, resume: ModelState) -> Command<Message> {
+        // The old client has to be gone before the API is initialized again, or dropping it
+        // afterwards would shut the new one down.
+        if let Some(client) = self.client.take() {
+            client.close();
+        }
+        let client = WorkshopClient::init_app(self.app_id, self.config.callback_poll_interval());
+
+        match client {
+            Ok(client) => {
+                self.client = Some(client);
+                match resume {
+                    ModelState::ItemForm(maybe_id, item_info) => {
+                        self.update_to_item_form(maybe_id, item_info)
+                    }
+                    ModelState::BatchUploading(queue, results) => {
+                        self.batch = queue[results.len()..].to_vec();
+                        self.state = ModelState::Initial(String::new());
+                        Command::none()
+                    }
+                    state => {
+                        self.state = state;
+                        Command::none()
+                    }
+                }
+            }
+            Err(err) => {
+                error_dialog(format!("Could not reconnect to Steam.\nError: {}", err).as_str());
+                Command::none()
+            }
+        }
+    }
+
+    fn state_name(&self) -> &'static str {
+        match self.state {
+            ModelState::Initial(_) => "Initial",
+            ModelState::ExistingIdSearching(..) => "ExistingIdSearching",
+            ModelState::ItemForm(..) => "ItemForm",
+            ModelState::SteamOffline(..) => "SteamOffline",
+            ModelState::CreatingItem(_) => "CreatingItem",
+            ModelState::CreationError(..) => "CreationError",
+            ModelState::SendingItem(..) => "SendingItem",
+            ModelState::SendingError(..) => "SendingError",
+            ModelState::SendingPreview(..) => "SendingPreview",
+            ModelState::PreviewError(..) => "PreviewError",
+            ModelState::SteamLost(_) => "SteamLost",
+            ModelState::Cancelled(..) => "Cancelled",
+            ModelState::BatchUploading(..) => "BatchUploading",
+            ModelState::BatchDone(..) => "BatchDone",
+            ModelState::Deleting(..) => "Deleting",
+            ModelState::DeletionError(..) => "DeletionError",
+            ModelState::Done(..) => "Done",
+        }
+    }
+
+    fn debug_info(&self) -> String {
+        let (item_id, form) = match &self.state {
+            ModelState::ItemForm(maybe_id, item_info) => {
+                (maybe_id.map(|id| id.0), Some(item_info.debug_json()))
+            }
+            _ => (None, None),
+        };
+
+        let info = serde_json::json!({
+            "state": self.state_name(),
+            "app_id": self.app_id.0,
+            "item_id": item_id,
+            "form": form,
+        });
+        serde_json::to_string_pretty(&info).unwrap_or_default()
+    }
+
+    fn is_sending(&self) -> bool {
+        matches!(
+            self.state,
+            ModelState::SendingItem(..)
+                | ModelState::SendingPreview(..)
+                | ModelState::BatchUploading(..)
+        )
+    }
+
+    fn start_busy_timer(&mut self) {
+        self.busy_since = Instant::now();
+        self.now = self.busy_since;
+    }
+
+    fn spinner(&self) -> char {
+        let elapsed = self.now.duration_since(self.busy_since).as_millis();
+        let frame = elapsed / SPINNER_INTERVAL.as_millis();
+        SPINNER_FRAMES[frame as usize % SPINNER_FRAMES.len()]
+    }
+
+    fn elapsed_secs(&self) -> u64 {
+        self.now.duration_since(self.busy_since).as_secs()
+    }
+
+    fn error_text(&self, err: &SteamError) -> String {
+        if self.config.show_error_details {
+            format!("{}\nDetails: {:?}", err.friendly_message(), err)
+        } else {
+            err.friendly_message()
+        }
+    }
+
+    fn upload_error_text(&self, err: &UploadError) -> String {
+        match err {
+            UploadError::Steam(err) => self.error_text(err),
+            _ => err.to_string(),
+        }
+    }
+
+    fn current_folder_scan(&self, item_info: &ItemInfoState) -> Option<&FolderScan> {
+        self.folder_scan
+            .as_ref()
+            .filter(|scan| scan.folder == item_info.target_folder())
+    }
+
+    fn scan_target_folder(
+        &mut self,
+        maybe_id: Option<PublishedFileId>,
+        folder: &Path,
+    ) -> Command<Message> {
+        self.folder_scan = None;
+        self.scan_in_progress = !folder.as_os_str().is_empty();
+
+        if self.scan_in_progress {
+            let folder = folder.to_path_buf();
+            Command::perform(
+                manifest::scan_folder(
+                    maybe_id,
+                    folder.clone(),
+                    self.config.exclude_patterns.clone(),
+                ),
+                move |scan| Message::ReceiveFolderScan(folder, scan),
+            )
+        } else {
+            Command::none()
+        }
+    }
+
+    fn search_item(&mut self, item_id: PublishedFileId, force_refresh: bool) -> Command<Message> {
+        let max_cache_age = if force_refresh {
+            0
+        } else {
+            self.config.item_cache_secs
+        };
+
+        self.state = ModelState::ExistingIdSearching(item_id, None);
+        self.start_busy_timer();
+        Command::perform(
+            self.client().clone().get_item_info(
+                item_id,
+                self.config.allowed_app_ids.clone(),
+                max_cache_age,
+                self.config.steam_timeout(),
+            ),
+            move |res| Message::receive_item_info(item_id, res),
+        )
+    }
+
+    fn update_to_item_form(
+        &mut self,
+        maybe_id: Option<PublishedFileId>,
+        mut item_info: ItemInfoState,
+    ) -> Command<Message> {
+        if let Some(last_version) = maybe_id.and_then(|id| self.versions.get(id)) {
+            item_info.suggest_version(last_version);
+        }
+        let folder = item_info.target_folder().to_path_buf();
+        let check_paths = item_info.check_paths().map(Message::EditItemData);
+        self.validation_report = None;
+        self.form_start = Some(item_info.clone());
+        self.state = ModelState::ItemForm(maybe_id, item_info);
+        Command::batch([check_paths, self.scan_target_folder(maybe_id, &folder)])
+    }
+
+    fn save_manifest(&mut self, item_id: PublishedFileId, folder: &Path) -> Command<Message> {
+        match self.folder_scan.take() {
+            Some(scan) if scan.folder == folder => {
+                if let Err(error) = scan.manifest.save(item_id) {
+                    log::warn!("Failed to save upload manifest: {}", error);
+                }
+                Command::none()
+            }
+            _ => Command::perform(
+                manifest::save_folder_manifest(item_id, folder.to_path_buf()),
+                |()| Message::ManifestSaved,
+            ),
+        }
+    }
+
+    fn remember_paths(&mut self, item_info: &ItemInfo) {
+        self.config.last_target_folder = Some(item_info.target_folder.clone());
+        if !item_info.preview_image.as_os_str().is_empty() {
+            self.config.last_preview_image = Some(item_info.preview_image.clone());
+        }
+        self.config.save();
+    }
+
+    fn validation_report(
+        &self,
+        maybe_id: Option<PublishedFileId>,
+        item_info: &ItemInfoState,
+    ) -> Vec<String> {
+        let mut failed = false;
+        let mut report = Vec::new();
+        let mut check = |result: Result<String, String>| match result {
+            Ok(line) => report.push(format!("\u{2713} {}", line)),
+            Err(line) => {
+                failed = true;
+                report.push(format!("\u{2717} {}", line));
+            }
+        };
+
+        check(
+            item_info
+                .validate(maybe_id)
+                .map(|info| format!("Item details for \"{}\" are valid.", info.name))
+                .map_err(|errors| errors.to_string()),
+        );
+        check(item_info.preview_check(maybe_id));
+
+        match self.current_folder_scan(item_info) {
+            _ if item_info.metadata_only() => check(Ok(
+                "Only the item's details will be updated; the folder is not uploaded.".to_string(),
+            )),
+            Some(scan) if scan.manifest.files.is_empty() => {
+                check(Err("Target folder is empty.".to_string()))
+            }
+            Some(scan) => {
+                let total_size = scan.manifest.total_size();
+                check(Ok(format!(
+                    "Target folder holds {} files, {}.",
+                    scan.manifest.files.len(),
+                    format_bytes(total_size)
+                )));
+                if total_size > LARGE_FOLDER_BYTES {
+                    report.push(format!(
+                        "! Over {}; you'll be asked to confirm before uploading.",
+                        format_bytes(LARGE_FOLDER_BYTES)
+                    ));
+                }
+                if !scan.hidden.is_empty() {
+                    report.push(format!(
+                        "! {} hidden entries will be {}.",
+                        scan.hidden.len(),
+                        if self.exclude_hidden_files {
+                            "left out"
+                        } else {
+                            "uploaded"
+                        }
+                    ));
+                }
+                if !scan.junk.is_empty() {
+                    report.push(format!(
+                        "! {} entries match the exclude patterns and will be left out.",
+                        scan.junk.len()
+                    ));
+                }
+            }
+            None => check(Err("Target folder could not be read.".to_string())),
+        }
+
+        report.insert(
+            0,
+            if failed {
+                "Validation failed. Nothing was uploaded.".to_string()
+            } else {
+                "Validation passed. Nothing was uploaded.".to_string()
+            },
+        );
+        report
+    }
+
+    fn confirm_update(
+        &self,
+        item_id: PublishedFileId,
+        item_state: &ItemInfoState,
+        item_info: &ItemInfo,
+    ) -> bool {
+        let name = match item_state.original_name() {
+            Some(original) if item_info::normalize_name(original) != item_info.name => {
+                format!("Name: \"{}\" (was \"{}\")", item_info.name, original)
+            }
+            _ => format!("Name: \"{}\" (unchanged)", item_info.name),
+        };
+        let preview = if item_info.preview_image.as_os_str().is_empty() {
+            "Preview: unchanged".to_string()
+        } else {
+            format!(
+                "Preview: replaced with {}",
+                item_info.preview_image.to_string_lossy()
+            )
+        };
+        if item_info.metadata_only {
+            return confirm_dialog(
+                format!(
+                    "This updates the details of item {}. The content is not re-uploaded.\n\n{}\n{}\n\nUpload the update?",
+                    item_id.0, name, preview
+                )
+                .as_str(),
+            );
+        }
+
+        let changes = match self
+            .current_folder_scan(item_state)
+            .and_then(|scan| scan.changes.as_ref())
+        {
+            Some(changes) => changes.to_string(),
+            None => "No record of the last upload to compare against.".to_string(),
+        };
+
+        confirm_dialog(
+            format!(
+                "This replaces the live content of item {}.\n\n{}\nFolder: {}\n{}\n{}\n\nUpload the update?",
+                item_id.0,
+                name,
+                item_info.target_folder.to_string_lossy(),
+                changes,
+                preview
+            )
+            .as_str(),
+        )
+    }
+
+    fn confirm_folder_size(&self, folder: &Path) -> bool {
+        // Uploads wait for the folder scan to finish, so a missing scan means the folder
+        // couldn't be read. The path checks report that; there's nothing to size here.
+        let manifest = match self
+            .folder_scan
+            .as_ref()
+            .filter(|scan| scan.folder == folder)
+        {
+            Some(scan) => &scan.manifest,
+            None => return true,
+        };
+
+        let total_size = manifest.total_size();
+        total_size <= LARGE_FOLDER_BYTES
+            || confirm_dialog(
+                format!(
+                    "The target folder holds {} across {} top-level entries.\nThat's unusually large for a Workshop item. Upload it anyway?",
+                    format_bytes(total_size),
+                    manifest.top_level_entries().len()
+                )
+                .as_str(),
+            )
+    }
+
+    fn update_to_upload(
+        &mut self,
+        maybe_id: Option<PublishedFileId>,
+        item_info: ItemInfo,
+    ) -> Command<Message> {
+        if !self.client().is_steam_running() {
+            self.state =
+                ModelState::SteamLost(Box::new(ModelState::ItemForm(maybe_id, item_info.into())));
+            return Command::none();
+        }
+
+        if !self.client().is_online() {
+            self.state = ModelState::SteamOffline(maybe_id, item_info);
+            return Command::none();
+        }
+
+        match maybe_id {
+            Some(item_id) => self.update_to_send_item(item_id, item_info),
+            None => self.update_to_create_item(item_info),
+        }
+    }
+
     fn update_to_create_item(&mut self, item_info: ItemInfo) -> Command<Message> {
         self.state = ModelState::CreatingItem(item_info);
-        Command::perform(self.client.clone().create_item(), Message::receive_item_id)
+        self.start_busy_timer();
+        Command::perform(
+            self.client()
+                .clone()
+                .create_item(self.config.steam_timeout()),
+            Message::receive_item_id,
+        )
     }
 
-    fn update_to_send_item(
+    fn stage_preview(&mut self, item_id: PublishedFileId, crop: bool) -> Option<PathBuf> {
+        if !crop {
+            return None;
+        }
+
+        let path = self.unabandoned_path(preview::cropped_path(item_id));
+        self.staged_paths.push(path.clone());
+        Some(path)
+    }
+
+    fn upload_next_batch_item(
         &mut self,
+        queue: Vec<BatchEntry>,
+        results: Vec<Result<PublishedFileId, UploadError>>,
+    ) -> Command<Message> {
+        let index = results.len();
+        save_batch_queue(&queue[index..]);
+        let (maybe_id, item_info) = match queue.get(index) {
+            Some(_) if self.batch_pause_requested => {
+                self.batch_pause_requested = false;
+                self.client().clear_upload_watch();
+                self.batch = queue[index..].to_vec();
+                let plural = if self.batch.len() == 1 { "" } else { "s" };
+                self.toasts.push(format!(
+                    "Batch paused with {} item{} left. Use \"Upload batch\" to resume.",
+                    self.batch.len(),
+                    plural
+                ));
+                self.state = ModelState::Initial(String::new());
+                return Command::none();
+            }
+            Some(entry) => entry.clone(),
+            None => {
+                self.client().clear_upload_watch();
+                self.state = ModelState::BatchDone(queue, results);
+                return Command::none();
+            }
+        };
+
+        self.state = ModelState::BatchUploading(queue, results);
+        self.upload_progress = None;
+
+        if !self.client().is_steam_running() || !self.client().is_online() {
+            let offline = Err(UploadError::Steam(SteamError::NoConnection));
+            return self.update(Message::ReceiveBatchResult(index, offline));
+        }
+
+        match maybe_id {
+            Some(item_id) => self.send_batch_item(index, item_id, item_info),
+            None => Command::perform(
+                self.client()
+                    .clone()
+                    .create_item(self.config.steam_timeout()),
+                move |res| Message::ReceiveBatchItemId(index, res),
+            ),
+        }
+    }
+
+    fn send_batch_item(
+        &mut self,
+        index: usize,
         item_id: PublishedFileId,
         item_info: ItemInfo,
     ) -> Command<Message> {
-        self.state = ModelState::SendingItem(item_id, item_info.clone());
+        Command::perform(self.prepare_send(item_id, item_info), move |res| {
+            Message::ReceiveBatchResult(index, res.map(|(item_id, _)| item_id))
+        })
+    }
+
+    fn theme_choice(&self) -> ThemeChoice {
+        self.config.theme.unwrap_or(self.system_theme)
+    }
+
+    fn key_action(&self, key_code: keyboard::KeyCode) -> Option<Message> {
+        use keyboard::KeyCode::{Enter, Escape, NumpadEnter};
+
+        match (&self.state, key_code) {
+            (ModelState::Initial(existing_id), Enter | NumpadEnter) => {
+                match parse_item_id(existing_id) {
+                    Err(error) if *error.kind() != IntErrorKind::Empty => None,
+                    _ => Some(Message::Proceed),
+                }
+            }
+            (ModelState::ItemForm(..) | ModelState::SteamOffline(..), Enter | NumpadEnter) => {
+                Some(Message::Proceed)
+            }
+            (
+                ModelState::CreationError(..)
+                | ModelState::SendingError(..)
+                | ModelState::PreviewError(..)
+                | ModelState::DeletionError(..)
+                | ModelState::SteamLost(_),
+                Enter | NumpadEnter,
+            ) => Some(Message::Retry),
+            (
+                ModelState::ExistingIdSearching(..)
+                | ModelState::ItemForm(..)
+                | ModelState::SteamOffline(..)
+                | ModelState::CreationError(..)
+                | ModelState::SendingError(..)
+                | ModelState::PreviewError(..)
+                | ModelState::DeletionError(..)
+                | ModelState::Cancelled(..)
+                | ModelState::BatchDone(..)
+                | ModelState::Done(..),
+                Escape,
+            ) => Some(Message::GoBack),
+            _ => None,
+        }
+    }
+
+    fn clear_pending_upload(&mut self, item_id: PublishedFileId) {
+        if self
+            .pending_upload
+            .as_ref()
+            .is_some_and(|pending| pending.item_id() == Some(item_id))
+        {
+            self.pending_upload = None;
+            Profile::clear_pending_upload();
+        }
+    }
+
+    fn save_preset(&mut self, item_info: ItemInfo) {
+        let profile = Profile::new(None, item_info);
+        let result = FileDialog::new()
+            .add_filter("Preset", &["json"])
+            .show_save_single_file();
+
+        let path = match result {
+            Ok(Some(path)) => path.with_extension("json"),
+            Ok(None) => return,
+            Err(err) => {
+                error_dialog(format!("Failed to choose a preset file. Error: {:?}", err).as_str());
+                return;
+            }
+        };
+
+        match profile.export(&path) {
+            Ok(()) => self
+                .toasts
+                .push(format!("Saved preset \"{}\".", profile.name)),
+            Err(err) => error_dialog(
+                format!(
+                    "Failed to save preset to \"{}\": {}",
+                    path.to_string_lossy(),
+                    err
+                )
+                .as_str(),
+            ),
+        }
+    }
+
+    fn confirm_discard(&self, item_info: &ItemInfoState) -> bool {
+        let edited = match &self.form_start {
+            Some(start) => item_info.has_edits_since(start),
+            None => true,
+        };
+
+        !edited || confirm_dialog("Discard your changes?")
+    }
+
+    fn confirm_delete(&self, item_id: PublishedFileId, item_info: &ItemInfoState) -> bool {
+        let name = match item_info.name().trim() {
+            "" => item_id.0.to_string(),
+            name => name.to_string(),
+        };
+
+        confirm_dialog(
+            format!(
+                "Permanently delete \"{}\" (item {}) from the Steam Workshop?\nSubscribers will lose it and this cannot be undone.",
+                name, item_id.0
+            )
+            .as_str(),
+        )
+    }
+
+    fn update_to_delete(
+        &mut self,
+        item_id: PublishedFileId,
+        item_info: ItemInfoState,
+    ) -> Command<Message> {
+        self.state = ModelState::Deleting(item_id, item_info);
+        self.start_busy_timer();
         Command::perform(
-            self.client.clone().send_item(item_id, item_info),
-            Message::receive_item_id,
+            self.client()
+                .clone()
+                .delete_item(item_id, self.config.steam_timeout()),
+            move |res| match res {
+                Ok(()) => Message::ReceiveDeleteDone(item_id),
+                Err(err) => Message::ReceiveSteamError(err),
+            },
         )
     }
+
+    fn update_to_send_preview(
+        &mut self,
+        item_id: PublishedFileId,
+        item_info: ItemInfoState,
+    ) -> Command<Message> {
+        let preview = match item_info.validate_preview() {
+            Ok(preview) => preview,
+            Err(error) => {
+                log::warn!("Error: {}", error);
+                return Command::none();
+            }
+        };
+
+        let cropped_preview = self.stage_preview(item_id, item_info.crop_preview());
+        self.state = ModelState::SendingPreview(item_id, item_info);
+        self.upload_progress = None;
+        self.upload_serial += 1;
+        let upload_serial = self.upload_serial;
+        let client = self.client().clone();
+        Command::perform(
+            async move {
+                let preview = crop_preview(preview, cropped_preview).await?;
+                client.send_preview(item_id, preview).await
+            },
+            move |res| Message::receive_upload_result(upload_serial, res),
+        )
+    }
+
+    fn prepare_send(
+        &mut self,
+        item_id: PublishedFileId,
+        mut item_info: ItemInfo,
+    ) -> impl Future<Output = Result<(PublishedFileId, bool), UploadError>> {
+        let cropped_preview = self.stage_preview(
+            item_id,
+            item_info.crop_preview && !item_info.preview_image.as_os_str().is_empty(),
+        );
+
+        let filter = ExcludeFilter {
+            hidden: self.exclude_hidden_files,
+            patterns: self.config.exclude_patterns.clone(),
+        };
+        let staging_dir = if item_info.metadata_only {
+            None
+        } else {
+            let dir = self.unabandoned_path(manifest::staging_dir(item_id));
+            self.staged_paths.push(dir.clone());
+            Some(dir)
+        };
+
+        let client = self.client().clone();
+        async move {
+            item_info.preview_image =
+                crop_preview(item_info.preview_image, cropped_preview).await?;
+            client
+                .send_filtered_item(item_id, item_info, filter, staging_dir)
+                .await
+        }
+    }
+
+    fn update_to_send_item(
+        &mut self,
+        item_id: PublishedFileId,
+        item_info: ItemInfo,
+    ) -> Command<Message> {
+        self.state = ModelState::SendingItem(item_id, item_info.clone());
+        self.upload_progress = None;
+        self.upload_serial += 1;
+        let upload_serial = self.upload_serial;
+
+        Command::perform(self.prepare_send(item_id, item_info), move |res| {
+            Message::receive_upload_result(upload_serial, res)
+        })
+    }
+
+    fn record_pending_upload(&mut self, item_id: PublishedFileId, item_info: &ItemInfo) {
+        let pending_upload = Profile::pending(item_id, item_info.clone());
+        pending_upload.save_pending_upload();
+        self.pending_upload = Some(pending_upload);
+    }
+
+    fn record_upload(
+        &mut self,
+        item_id: PublishedFileId,
+        item_info: &ItemInfo,
+    ) -> Command<Message> {
+        self.clear_pending_upload(item_id);
+        let save_manifest = if item_info.metadata_only {
+            Command::none()
+        } else {
+            self.remember_paths(item_info);
+            self.save_manifest(item_id, &item_info.target_folder)
+        };
+        self.recent_items.push(item_id, item_info.name.clone());
+        self.recent_items.save();
+        let last_upload = Profile::new(Some(item_id), item_info.clone());
+        last_upload.save_last_upload();
+        self.last_upload = Some(last_upload);
+        if let Some(version) = item_info.version.clone() {
+            self.versions.set(item_id, version);
+            self.versions.save();
+        }
+        save_manifest
+    }
+
+    fn remove_staged_paths(&mut self) {
+        if let Some(client) = &self.client {
+            client.clear_upload_watch();
+        }
+        remove_paths(self.staged_paths.drain(..));
+    }
+
+    // Steam can't abort an update, so a cancelled upload may still be reading its staged
+    // files. They're kept until that upload's result arrives, or until the app exits.
+    fn abandon_staged_paths(&mut self, upload_serial: u64) {
+        let paths = std::mem::take(&mut self.staged_paths);
+        self.abandoned_paths.push((upload_serial, paths));
+    }
+
+    fn remove_abandoned_paths(&mut self, upload_serial: u64) {
+        let (finished, still_running) = std::mem::take(&mut self.abandoned_paths)
+            .into_iter()
+            .partition(|(serial, _)| *serial == upload_serial);
+        self.abandoned_paths = still_running;
+        remove_paths(finished.into_iter().flat_map(|(_, paths)| paths));
+    }
+
+    // A retry mustn't restage into a folder that an abandoned upload is still reading.
+    fn unabandoned_path(&self, path: PathBuf) -> PathBuf {
+        let abandoned = self
+            .abandoned_paths
+            .iter()
+            .any(|(_, paths)| paths.contains(&path));
+        if !abandoned {
+            return path;
+        }
+
+        let mut name = path.file_stem().unwrap_or_default().to_os_string();
+        name.push(format!("_{}", self.upload_serial));
+        if let Some(extension) = path.extension() {
+            name.push(".");
+            name.push(extension);
+        }
+        path.with_file_name(name)
+    }
+}
+
+async fn crop_preview(
+    preview: PathBuf,
+    cropped_path: Option<PathBuf>,
+) -> Result<PathBuf, UploadError> {
+    match cropped_path {
+        Some(cropped_path) => preview::crop_to_square(preview, cropped_path)
+            .await
+            .map_err(UploadError::PreviewCropFailed),
+        None => Ok(preview),
+    }
+}
+
+fn remove_paths(paths: impl IntoIterator<Item = PathBuf>) {
+    for path in paths {
+        if !path.exists() {
+            continue;
+        }
+
+        let result = if path.is_dir() {
+            std::fs::remove_dir_all(&path)
+        } else {
+            std::fs::remove_file(&path)
+        };
+
+        if let Err(error) = result {
+            log::warn!("Failed to remove staged upload file {:?}: {}", path, error);
+        }
+    }
 }
 
 impl Application for Model {
@@ -142,21 +1434,162 @@ impl Application for Model {
     type Flags = WorkshopClient;
     type Theme = iced::Theme;
 
-    fn new(client: Self::Flags) -> (Self, Command<Self::Message>) {
-        let state = ModelState::Initial(String::new());
+    fn new(client: Self::Flags) -> (Self, Command<Self::Message>) {
+        let state = ModelState::Initial(String::new());
+        let config = Config::load();
+        // Only a theme that follows the system needs the (slow) system probe.
+        let detect_theme = match config.theme {
+            Some(_) => Command::none(),
+            None => Command::perform(ThemeChoice::system(), Message::ReceiveSystemTheme),
+        };
+
+        (
+            Model {
+                app_id: client.app_id(),
+                persona_name: client.persona_name(),
+                client: Some(client),
+                state,
+                folder_scan: None,
+                scan_in_progress: false,
+                config,
+                system_theme: ThemeChoice::Dark,
+                profiles: Profiles::load(),
+                versions: VersionHistory::load(),
+                recent_items: RecentItems::load(),
+                batch: Profile::load_batch_queue()
+                    .into_iter()
+                    .map(|entry| (entry.item_id(), entry.item_info))
+                    .collect(),
+                batch_pause_requested: false,
+                test_upload: None,
+                form_start: None,
+                validation_report: None,
+                should_exit: false,
+                last_upload: Profile::load_last_upload(),
+                pending_upload: Profile::load_pending_upload(),
+                show_folder_contents: false,
+                legal_agreement_accepted: None,
+                exclude_hidden_files: false,
+                staged_paths: Vec::new(),
+                abandoned_paths: Vec::new(),
+                busy_since: Instant::now(),
+                now: Instant::now(),
+                upload_progress: None,
+                upload_serial: 0,
+                toasts: Toasts::default(),
+            },
+            detect_theme,
+        )
+    }
+
+    fn title(&self) -> String {
+        format!(
+            "4onen's Workshop Uploader - Signed in as {}",
+            self.persona_name
+        )
+    }
+
+    fn update(&mut self, message: Self::Message) -> Command<Message> {
+        const CMDN: Command<Message> = Command::none();
+
+        if std::mem::discriminant(&message) == std::mem::discriminant(&Message::TermsLinkPressed) {
+            if let Some(client) = &self.client {
+                client.open_terms();
+            }
+            return CMDN;
+        }
+
+        if message == Message::ToggleFolderContents {
+            self.show_folder_contents = !self.show_folder_contents;
+            return CMDN;
+        }
+
+        if let Message::SetExcludeHiddenFiles(exclude) = message {
+            self.exclude_hidden_files = exclude;
+            return CMDN;
+        }
+
+        if let Message::ReceiveSystemTheme(theme) = message {
+            self.system_theme = theme;
+            return CMDN;
+        }
+
+        if message == Message::ToggleTheme {
+            self.config.theme = Some(self.theme_choice().toggled());
+            self.config.save();
+            return CMDN;
+        }
+
+        if message == Message::CopyDebugInfo {
+            self.toasts.push("Copied debug info to clipboard.");
+            return iced::clipboard::write(self.debug_info());
+        }
 
-        (Model { client, state }, Command::none())
-    }
+        match message {
+            Message::WindowResized(width, height) => {
+                self.config.set_window_size(width, height);
+                return CMDN;
+            }
+            Message::WindowMoved(x, y) => {
+                self.config.set_window_position(x, y);
+                return CMDN;
+            }
+            Message::CloseRequested => {
+                self.config.save();
+                remove_paths(
+                    std::mem::take(&mut self.abandoned_paths)
+                        .into_iter()
+                        .flat_map(|(_, paths)| paths),
+                );
+                if let Some(client) = &self.client {
+                    client.shutdown();
+                }
+                self.should_exit = true;
+                return CMDN;
+            }
+            Message::KeyPressed(key_code) => {
+                return match self.key_action(key_code) {
+                    Some(message) => self.update(message),
+                    None => CMDN,
+                };
+            }
+            _ => (),
+        }
 
-    fn title(&self) -> String {
-        String::from("4onen's Workshop Uploader")
-    }
+        if let Message::Tick(now) = message {
+            self.now = now;
+            self.toasts.expire(now);
+            if let Some(client) = self.client.as_ref().filter(|_| self.is_sending()) {
+                self.upload_progress = client.upload_progress();
+            }
+            return CMDN;
+        }
 
-    fn update(&mut self, message: Self::Message) -> Command<Message> {
-        const CMDN: Command<Message> = Command::none();
+        if let Message::SetShowErrorDetails(show) = message {
+            self.config.show_error_details = show;
+            self.config.save();
+            return CMDN;
+        }
 
-        if std::mem::discriminant(&message) == std::mem::discriminant(&Message::TermsLinkPressed) {
-            self.client.open_terms();
+        let is_steam_error = match message {
+            Message::ReceiveSearchError(..) | Message::ReceiveSteamError(_) => true,
+            Message::ReceiveUploadError(serial, _) => serial == self.upload_serial,
+            _ => false,
+        };
+        match message {
+            Message::ReceiveUploadDone(serial, ..) | Message::ReceiveUploadError(serial, _)
+                if serial != self.upload_serial =>
+            {
+                self.remove_abandoned_paths(serial);
+            }
+            _ => (),
+        }
+        if is_steam_error {
+            log::error!("Steam error in state {}: {:?}", self.state_name(), message);
+        }
+        if is_steam_error && !my_steamworks::steam_running() {
+            self.remove_staged_paths();
+            self.state = ModelState::SteamLost(Box::new(self.resume_state()));
             return CMDN;
         }
 
@@ -166,50 +1599,265 @@ impl Application for Model {
                     self.state = ModelState::Initial(idstr);
                     CMDN
                 }
-                Message::Proceed => match idstr.parse::<u64>().map(PublishedFileId) {
-                    Ok(item_id) => {
-                        self.state = ModelState::ExistingIdSearching(item_id, None);
-                        Command::perform(
-                            self.client.clone().get_item_info(item_id),
-                            Message::receive_item_info,
-                        )
+                Message::ResumePendingUpload => match self.pending_upload.clone() {
+                    Some(pending) => {
+                        self.update_to_item_form(pending.item_id(), pending.item_info.into())
+                    }
+                    None => CMDN,
+                },
+                Message::DiscardPendingUpload => {
+                    if let Some(item_id) = self.pending_upload.as_ref().and_then(Profile::item_id) {
+                        self.clear_pending_upload(item_id);
+                    }
+                    CMDN
+                }
+                Message::ReopenLastUpload => match self.last_upload.clone() {
+                    Some(last_upload) => self
+                        .update_to_item_form(last_upload.item_id(), last_upload.item_info.into()),
+                    None => CMDN,
+                },
+                Message::LoadProfile(name) => match self.profiles.get(&name).cloned() {
+                    Some(profile) => {
+                        self.update_to_item_form(profile.item_id(), profile.item_info.into())
                     }
+                    None => CMDN,
+                },
+                Message::SelectRecentItem(item) => self.search_item(item.item_id(), false),
+                Message::ClearBatch => {
+                    self.batch.clear();
+                    save_batch_queue(&self.batch);
+                    CMDN
+                }
+                Message::StartBatch if !self.batch.is_empty() => {
+                    let queue = std::mem::take(&mut self.batch);
+                    self.batch_pause_requested = false;
+                    self.upload_next_batch_item(queue, Vec::new())
+                }
+                Message::Proceed => match parse_item_id(&idstr) {
+                    Ok(item_id) => self.search_item(item_id, false),
                     _ => {
-                        self.state = ModelState::ItemForm(None, ItemInfoState::default());
-                        CMDN
+                        let item_info = ItemInfoState::with_paths(
+                            self.config
+                                .last_preview_image
+                                .clone()
+                                .filter(|path| path.is_file()),
+                            self.config
+                                .last_target_folder
+                                .clone()
+                                .filter(|path| path.is_dir()),
+                        );
+                        self.update_to_item_form(None, item_info)
                     }
                 },
                 _ => CMDN,
             },
-            ModelState::ExistingIdSearching(item_id, _) => {
-                match message {
-                    Message::GoBack => self.state = ModelState::Initial(item_id.0.to_string()),
-                    Message::ReceiveFoundItemInfo(item_info) => {
-                        self.state = ModelState::ItemForm(Some(item_id), item_info.into())
-                    }
-                    Message::ReceiveSteamError(err) => {
-                        self.state = ModelState::ExistingIdSearching(item_id, Some(err))
+            ModelState::ExistingIdSearching(item_id, _) => match message {
+                Message::GoBack => {
+                    self.state = ModelState::Initial(item_id.0.to_string());
+                    CMDN
+                }
+                Message::ReceiveFoundItemInfo(found_id, item_info, always_allow)
+                    if found_id == item_id =>
+                {
+                    if let Some(app_id) = always_allow {
+                        self.config.allowed_app_ids.insert(app_id.0);
+                        self.config.save();
                     }
-                    _ => (),
-                };
-                CMDN
-            }
+                    let download = match item_info.preview_url.clone() {
+                        Some(url) => Command::perform(preview::download(url), move |bytes| {
+                            Message::ReceiveCurrentPreview(item_id, bytes)
+                        }),
+                        None => CMDN,
+                    };
+                    Command::batch([
+                        self.update_to_item_form(
+                            Some(item_id),
+                            ItemInfoState::from_original(item_info),
+                        ),
+                        download,
+                    ])
+                }
+                Message::ReceiveSearchError(found_id, err) if found_id == item_id => {
+                    self.state = ModelState::ExistingIdSearching(item_id, Some(err));
+                    CMDN
+                }
+                _ => CMDN,
+            },
             ModelState::ItemForm(maybe_id, mut item_info) => match message {
                 Message::EditItemData(item_info_message) => {
-                    item_info.update(item_info_message);
+                    self.validation_report = None;
+                    if matches!(
+                        item_info_message,
+                        ItemInfoMessage::CopyPreviewImagePath
+                            | ItemInfoMessage::CopyTargetFolderPath
+                    ) {
+                        self.toasts.push("Copied path to clipboard.");
+                    }
+
+                    // Path checks can autofill the name; that isn't an edit to discard.
+                    let unedited = matches!(item_info_message, ItemInfoMessage::PathsChecked(_))
+                        && self
+                            .form_start
+                            .as_ref()
+                            .is_some_and(|start| !item_info.has_edits_since(start));
+
+                    let old_folder = item_info.target_folder().to_path_buf();
+                    let command = item_info.update(item_info_message);
+                    let new_folder = item_info.target_folder().to_path_buf();
+                    if unedited {
+                        self.form_start = Some(item_info.clone());
+                    }
+                    self.state = ModelState::ItemForm(maybe_id, item_info);
+
+                    if old_folder != new_folder {
+                        Command::batch([
+                            command.map(Message::EditItemData),
+                            self.scan_target_folder(maybe_id, &new_folder),
+                        ])
+                    } else {
+                        command.map(Message::EditItemData)
+                    }
+                }
+                Message::FileDropped(path) => match item_info::dropped_path_message(&path) {
+                    Some(item_info_message) => {
+                        self.update(Message::EditItemData(item_info_message))
+                    }
+                    None => CMDN,
+                },
+                Message::ReceiveCurrentPreview(item_id, Some(bytes))
+                    if maybe_id == Some(item_id) =>
+                {
+                    item_info.set_current_preview(bytes);
                     self.state = ModelState::ItemForm(maybe_id, item_info);
                     CMDN
                 }
-                Message::Proceed => match ItemInfo::try_from(item_info.clone()) {
-                    Ok(item_info) => match maybe_id {
-                        Some(item_id) => self.update_to_send_item(item_id, item_info),
-                        None => self.update_to_create_item(item_info),
-                    },
+                Message::ReceiveFolderScan(folder, scan) => {
+                    if folder == item_info.target_folder() {
+                        self.folder_scan = scan;
+                        self.scan_in_progress = false;
+                    }
+                    CMDN
+                }
+                Message::SaveProfile => {
+                    if let Ok(item_info) = item_info.validate(maybe_id) {
+                        let profile = Profile::new(maybe_id, item_info);
+                        self.toasts
+                            .push(format!("Saved profile \"{}\".", profile.name));
+                        self.profiles.insert(profile);
+                        self.profiles.save();
+                    }
+                    CMDN
+                }
+                Message::SavePreset => {
+                    if let Ok(item_info) = item_info.validate(maybe_id) {
+                        self.save_preset(item_info);
+                    }
+                    CMDN
+                }
+                Message::LoadPreset => match load_preset() {
+                    Some(profile) => {
+                        self.toasts
+                            .push(format!("Loaded preset \"{}\".", profile.name));
+                        item_info.load_preset(profile.item_info);
+                        let folder = item_info.target_folder().to_path_buf();
+                        let check_paths = item_info.check_paths().map(Message::EditItemData);
+                        self.state = ModelState::ItemForm(maybe_id, item_info);
+                        Command::batch([check_paths, self.scan_target_folder(maybe_id, &folder)])
+                    }
+                    None => CMDN,
+                },
+                Message::Validate => {
+                    self.validation_report = Some(self.validation_report(maybe_id, &item_info));
+                    CMDN
+                }
+                Message::AddToBatch if self.scan_in_progress => CMDN,
+                Message::AddToBatch => match item_info.validate_now(maybe_id) {
+                    Ok(new_info)
+                        if maybe_id.is_some_and(|item_id| {
+                            !self.confirm_update(item_id, &item_info, &new_info)
+                        }) =>
+                    {
+                        CMDN
+                    }
+                    Ok(new_info)
+                        if !new_info.metadata_only
+                            && !self.confirm_folder_size(&new_info.target_folder) =>
+                    {
+                        CMDN
+                    }
+                    Ok(new_info) => {
+                        self.toasts
+                            .push(format!("Added \"{}\" to the batch.", new_info.name));
+                        self.batch.push((maybe_id, new_info));
+                        save_batch_queue(&self.batch);
+                        self.state = ModelState::Initial(String::new());
+                        CMDN
+                    }
+                    Err(_) => {
+                        self.state = ModelState::ItemForm(maybe_id, item_info);
+                        CMDN
+                    }
+                },
+                Message::UpdatePreviewOnly => match maybe_id {
+                    Some(item_id) => self.update_to_send_preview(item_id, item_info),
+                    None => CMDN,
+                },
+                Message::DeleteItem => match maybe_id {
+                    Some(item_id) if self.confirm_delete(item_id, &item_info) => {
+                        self.update_to_delete(item_id, item_info)
+                    }
+                    _ => CMDN,
+                },
+                Message::TestUpload if self.scan_in_progress || maybe_id.is_some() => CMDN,
+                Message::TestUpload => match item_info.validate_now(None) {
+                    Ok(new_info)
+                        if !new_info.metadata_only
+                            && !self.confirm_folder_size(&new_info.target_folder) =>
+                    {
+                        CMDN
+                    }
+                    Ok(mut new_info) => {
+                        new_info.visibility = Visibility::Private;
+                        self.test_upload = Some(new_info.clone());
+                        self.update_to_upload(None, new_info)
+                    }
+                    Err(_) => {
+                        self.state = ModelState::ItemForm(None, item_info);
+                        CMDN
+                    }
+                },
+                Message::Proceed if self.scan_in_progress => CMDN,
+                Message::Proceed => match item_info.validate_now(maybe_id) {
+                    Ok(new_info)
+                        if maybe_id.is_some_and(|item_id| {
+                            !self.confirm_update(item_id, &item_info, &new_info)
+                        }) =>
+                    {
+                        CMDN
+                    }
+                    Ok(item_info)
+                        if !item_info.metadata_only
+                            && !self.confirm_folder_size(&item_info.target_folder) =>
+                    {
+                        CMDN
+                    }
+                    Ok(item_info) => {
+                        self.test_upload = None;
+                        self.update_to_upload(maybe_id, item_info)
+                    }
                     Err(error) => {
-                        println!("Error: {}", error);
+                        log::warn!("Error: {}", error);
+                        self.state = ModelState::ItemForm(maybe_id, item_info);
                         CMDN
                     }
                 },
+                Message::RefreshItem => match maybe_id {
+                    Some(item_id) if self.confirm_discard(&item_info) => {
+                        self.search_item(item_id, true)
+                    }
+                    _ => CMDN,
+                },
+                Message::GoBack if !self.confirm_discard(&item_info) => CMDN,
                 Message::GoBack => {
                     self.state = ModelState::Initial(
                         maybe_id
@@ -220,58 +1868,180 @@ impl Application for Model {
                 }
                 _ => CMDN,
             },
+            ModelState::SteamOffline(maybe_id, item_info) => match message {
+                Message::Proceed => self.update_to_upload(maybe_id, item_info),
+                Message::GoBack => self.update_to_item_form(maybe_id, item_info.into()),
+                _ => CMDN,
+            },
             ModelState::CreatingItem(item_info) => match message {
-                Message::ReceiveItemId(item_id) => self.update_to_send_item(item_id, item_info),
+                Message::ReceiveItemId(item_id, needs_legal_agreement) => {
+                    self.legal_agreement_accepted = Some(!needs_legal_agreement);
+                    self.record_pending_upload(item_id, &item_info);
+                    self.update_to_send_item(item_id, item_info)
+                }
                 Message::ReceiveSteamError(err) => {
                     self.state = ModelState::CreationError(item_info, err);
                     CMDN
                 }
                 _ => CMDN,
             },
-            ModelState::CreationError(item_info, _err) => {
-                match message {
-                    Message::GoBack => self.state = ModelState::ItemForm(None, item_info.into()),
-                    _ => (),
-                };
-                CMDN
-            }
+            ModelState::CreationError(item_info, _err) => match message {
+                Message::GoBack => self.update_to_item_form(None, item_info.into()),
+                Message::Retry => self.update_to_upload(None, item_info),
+                _ => CMDN,
+            },
             ModelState::SendingItem(item_id, item_info) => {
                 match message {
-                    Message::ReceiveItemId(incoming_id) => {
+                    Message::ReceiveUploadDone(serial, incoming_id, needs_legal_agreement)
+                        if serial == self.upload_serial =>
+                    {
+                        self.remove_staged_paths();
+                        self.legal_agreement_accepted = Some(!needs_legal_agreement);
                         if incoming_id != item_id {
-                            println!(
+                            log::warn!(
                                 "Not advancing due to non-matching ids. Expected {}, got {}.",
-                                item_id.0, incoming_id.0,
+                                item_id.0,
+                                incoming_id.0,
                             );
                         } else {
-                            self.state = ModelState::Done(item_id);
+                            self.state = ModelState::Done(item_id, item_info.visibility);
+                            return self.record_upload(item_id, &item_info);
                         };
                     }
-                    Message::ReceiveSteamError(err) => {
+                    Message::ReceiveUploadError(serial, err) if serial == self.upload_serial => {
+                        self.remove_staged_paths();
                         self.state = ModelState::SendingError(item_id, item_info, err);
                     }
+                    Message::CancelUpload => {
+                        self.abandon_staged_paths(self.upload_serial);
+                        self.upload_serial += 1;
+                        self.client().clear_upload_watch();
+                        self.state = ModelState::Cancelled(item_id, item_info);
+                    }
                     _ => (),
                 };
                 CMDN
             }
-            ModelState::SendingError(item_id, item_info, _err) => {
+            ModelState::SendingPreview(item_id, item_info) => {
                 match message {
-                    Message::GoBack => {
-                        self.state = ModelState::ItemForm(item_id.into(), item_info.into())
+                    Message::ReceiveUploadDone(serial, _, needs_legal_agreement)
+                        if serial == self.upload_serial =>
+                    {
+                        self.remove_staged_paths();
+                        self.legal_agreement_accepted = Some(!needs_legal_agreement);
+                        self.state = ModelState::Done(item_id, Visibility::KeepCurrent);
+                    }
+                    Message::ReceiveUploadError(serial, err) if serial == self.upload_serial => {
+                        self.remove_staged_paths();
+                        self.state = ModelState::PreviewError(item_id, item_info, err);
                     }
                     _ => (),
                 };
                 CMDN
             }
-            ModelState::Done(item_id) => {
+            ModelState::PreviewError(item_id, item_info, _err) => match message {
+                Message::GoBack => self.update_to_item_form(Some(item_id), item_info),
+                Message::Retry => self.update_to_send_preview(item_id, item_info),
+                _ => CMDN,
+            },
+            ModelState::BatchUploading(mut queue, mut results) => match message {
+                Message::PauseBatch => {
+                    self.batch_pause_requested = true;
+                    CMDN
+                }
+                Message::ReceiveBatchItemId(index, result) if index == results.len() => {
+                    match result {
+                        Ok((item_id, needs_legal_agreement)) => {
+                            self.legal_agreement_accepted = Some(!needs_legal_agreement);
+                            self.record_pending_upload(item_id, &queue[index].1);
+                            queue[index].0 = Some(item_id);
+                            save_batch_queue(&queue[index..]);
+                            let (_, item_info) = queue[index].clone();
+                            self.state = ModelState::BatchUploading(queue, results);
+                            self.send_batch_item(index, item_id, item_info)
+                        }
+                        Err(err) => {
+                            results.push(Err(UploadError::Steam(err)));
+                            self.upload_next_batch_item(queue, results)
+                        }
+                    }
+                }
+                Message::ReceiveBatchResult(index, result) if index == results.len() => {
+                    self.remove_staged_paths();
+                    let save_manifest = match result {
+                        Ok(item_id) => {
+                            let (_, item_info) = queue[index].clone();
+                            self.record_upload(item_id, &item_info)
+                        }
+                        Err(_) => CMDN,
+                    };
+                    results.push(result);
+                    Command::batch([save_manifest, self.upload_next_batch_item(queue, results)])
+                }
+                _ => CMDN,
+            },
+            ModelState::BatchDone(..) => match message {
+                Message::GoBack => {
+                    self.state = ModelState::Initial(String::new());
+                    CMDN
+                }
+                _ => CMDN,
+            },
+            ModelState::Deleting(item_id, item_info) => match message {
+                Message::ReceiveDeleteDone(deleted_id) if deleted_id == item_id => {
+                    self.clear_pending_upload(item_id);
+                    self.recent_items.remove(item_id);
+                    self.recent_items.save();
+                    self.toasts.push(format!("Deleted item {}.", item_id.0));
+                    self.state = ModelState::Initial(String::new());
+                    CMDN
+                }
+                Message::ReceiveSteamError(err) => {
+                    self.state = ModelState::DeletionError(item_id, item_info, err);
+                    CMDN
+                }
+                _ => CMDN,
+            },
+            ModelState::DeletionError(item_id, item_info, _err) => match message {
+                Message::GoBack => self.update_to_item_form(Some(item_id), item_info),
+                Message::Retry => self.update_to_delete(item_id, item_info),
+                _ => CMDN,
+            },
+            ModelState::Cancelled(item_id, item_info) => match message {
+                Message::GoBack => self.update_to_item_form(Some(item_id), item_info.into()),
+                _ => CMDN,
+            },
+            ModelState::SteamLost(resume) => match message {
+                Message::Retry => self.reconnect(*resume),
+                _ => CMDN,
+            },
+            ModelState::SendingError(item_id, item_info, _err) => match message {
+                Message::GoBack => self.update_to_item_form(item_id.into(), item_info.into()),
+                Message::Retry => self.update_to_send_item(item_id, item_info),
+                _ => CMDN,
+            },
+            ModelState::Done(item_id, _) => {
                 match message {
                     Message::Proceed => {
-                        let item_url = format!("steam://url/CommunityFilePage/{}", item_id.0);
-                        self.client.open_url(item_url.as_str());
+                        self.client().open_url(item_url(item_id).as_str());
+                    }
+                    Message::DeleteItem => {
+                        if let Some(item_info) = self.test_upload.clone() {
+                            let item_info = ItemInfoState::from(item_info);
+                            if self.confirm_delete(item_id, &item_info) {
+                                self.test_upload = None;
+                                return self.update_to_delete(item_id, item_info);
+                            }
+                        }
                     }
                     Message::GoBack => {
+                        self.test_upload = None;
                         self.state = ModelState::Initial(String::default());
                     }
+                    Message::CopyItemLink => {
+                        self.toasts.push("Copied item link to clipboard.");
+                        return iced::clipboard::write(item_url(item_id));
+                    }
                     _ => (),
                 };
                 CMDN
@@ -279,65 +2049,316 @@ impl Application for Model {
         }
     }
 
-    fn view(&self) -> Element<Self::Message> {
+    fn subscription(&self) -> Subscription<Message> {
+        let busy = matches!(
+            self.state,
+            ModelState::ExistingIdSearching(_, None)
+                | ModelState::CreatingItem(_)
+                | ModelState::Deleting(..)
+        );
+
+        let tick = if busy || self.is_sending() {
+            iced::time::every(SPINNER_INTERVAL).map(Message::Tick)
+        } else if !self.toasts.is_empty() {
+            iced::time::every(Duration::from_secs(1)).map(Message::Tick)
+        } else {
+            Subscription::none()
+        };
+
+        Subscription::batch([tick, iced::subscription::events_with(window_event)])
+    }
+
+    fn theme(&self) -> Self::Theme {
+        self.theme_choice().theme()
+    }
+
+    fn should_exit(&self) -> bool {
+        self.should_exit
+    }
+
+    fn view(&self) -> Element<'_, Self::Message> {
+        let signed_in = text(format!("Signed in as {}", self.persona_name)).size(14);
+        if self.toasts.is_empty() {
+            column![signed_in, self.state_view()].into()
+        } else {
+            column![signed_in, self.toasts.view(), self.state_view()].into()
+        }
+    }
+}
+
+impl Model {
+    fn state_view(&self) -> Element<'_, Message> {
         match &self.state {
-            ModelState::Initial(existing_id) => initial_view(existing_id.as_str()),
+            ModelState::Initial(existing_id) => {
+                let initial = initial_view(
+                    existing_id.as_str(),
+                    &self.config,
+                    &self.profiles,
+                    self.last_upload.as_ref(),
+                    &self.recent_items,
+                    &self.batch,
+                    self.theme_choice(),
+                );
+
+                match &self.pending_upload {
+                    Some(pending) => column![pending_upload_view(pending), initial].into(),
+                    None => initial,
+                }
+            }
             ModelState::ExistingIdSearching(item_id, None) => column![
-                text(format!("Searching for item with ID {}...", item_id.0)),
+                item_info::skeleton_view(*item_id).map(Message::EditItemData),
+                text(format!(
+                    "{} Searching... {}s",
+                    self.spinner(),
+                    self.elapsed_secs()
+                )),
                 button("Cancel").on_press(Message::GoBack),
             ]
             .into(),
             ModelState::ExistingIdSearching(item_id, Some(e)) => column![
                 text(format!(
-                    "Search for item with ID {} failed.\nError: {:?}",
-                    item_id.0, e
+                    "Search for item with ID {} failed.\nError: {}",
+                    item_id.0,
+                    self.error_text(e)
                 )),
                 button("Go Back").on_press(Message::GoBack),
             ]
             .into(),
-            ModelState::ItemForm(item_id, item_state) => edit_item_view(item_state, *item_id),
+            ModelState::ItemForm(item_id, item_state) => {
+                let form = edit_item_view(
+                    item_state,
+                    *item_id,
+                    self.current_folder_scan(item_state),
+                    self.scan_in_progress,
+                    self.exclude_hidden_files,
+                    self.show_folder_contents,
+                    self.legal_agreement_accepted,
+                );
+
+                let mut res = column![form];
+                if let Some(report) = &self.validation_report {
+                    res = res.push(text(report.join("\n")).size(14));
+                }
+                if self.config.show_error_details {
+                    res = res.push(button("Copy debug info").on_press(Message::CopyDebugInfo));
+                }
+                res.into()
+            }
+            ModelState::SteamOffline(_, item_info) => column![
+                text(format!(
+                    "Steam appears to be offline.\n\"{}\" can't be uploaded until Steam reconnects to its servers.",
+                    item_info.name
+                )),
+                row![
+                    button("Go Back").on_press(Message::GoBack),
+                    button("Retry").on_press(Message::Proceed),
+                ],
+            ]
+            .into(),
             ModelState::CreatingItem(item_info) => {
-                text(format!("Creating \"{}\" on Steam Workshop...", item_info.name).as_str()).into()
+                busy_view(self.spinner(), format!(
+                    "Creating \"{}\" on Steam Workshop... {}s",
+                    item_info.name,
+                    self.elapsed_secs()
+                ))
             }
             ModelState::CreationError(item_info, err) => column![text(format!(
-                "Error creating a new entry on the workshop:\n{:?}\n\"{}\" was not uploaded.",
-                err, item_info.name
+                "Error creating a new entry on the workshop:\n{}\n\"{}\" was not uploaded.",
+                self.error_text(err), item_info.name
             )),
-            button("Go Back").on_press(Message::GoBack),
+            row![
+                button("Go Back").on_press(Message::GoBack),
+                button("Retry").on_press(Message::Retry),
+            ],
+            ]
+            .into(),
+            ModelState::SendingItem(item_id, _item_info) => column![
+                busy_view(self.spinner(), format!("Sending item {} to Steam Workshop...", item_id.0)),
+                upload_progress_view(self.upload_progress),
+                button("Cancel").on_press(Message::CancelUpload),
+            ]
+            .into(),
+            ModelState::SendingPreview(item_id, _item_info) => column![
+                busy_view(self.spinner(), format!("Sending a new preview for item {}...", item_id.0)),
+                upload_progress_view(self.upload_progress),
+            ]
+            .into(),
+            ModelState::PreviewError(item_id, _item_info, err) => column![
+                text(format!(
+                    "Error updating the preview of item {}:\n{}\nThe item's content was not touched.",
+                    item_id.0,
+                    self.upload_error_text(err)
+                )),
+                row![
+                    button("Go Back").on_press(Message::GoBack),
+                    button("Retry").on_press(Message::Retry),
+                ],
+            ]
+            .into(),
+            ModelState::Cancelled(item_id, item_info) => column![
+                text(format!(
+                    "Stopped waiting for the upload of \"{}\".\nItem {} exists on the Workshop, but your content was not confirmed as committed.\nSteam can't abort an update once it is submitted, so it may still finish in the background. Check the item's page before uploading again.",
+                    item_info.name, item_id.0
+                )),
+                button("Go Back").on_press(Message::GoBack),
+            ]
+            .into(),
+            ModelState::BatchUploading(queue, results) => column![
+                batch_view(queue, results),
+                upload_progress_view(self.upload_progress),
+                if self.batch_pause_requested {
+                    Element::from(text("Pausing after the current item..."))
+                } else {
+                    button("Pause after this item")
+                        .on_press(Message::PauseBatch)
+                        .into()
+                },
+            ]
+            .into(),
+            ModelState::BatchDone(queue, results) => column![
+                text(format!(
+                    "Batch finished: {} of {} items uploaded.",
+                    results.iter().filter(|result| result.is_ok()).count(),
+                    queue.len()
+                )),
+                batch_view(queue, results),
+                button("Go Back").on_press(Message::GoBack),
             ]
             .into(),
-            ModelState::SendingItem(item_id, _item_info) => {
-                text(format!("Sending item {} to Steam Workshop...", item_id.0).as_str()).into()
+            ModelState::Deleting(item_id, _item_info) => {
+                busy_view(self.spinner(), format!("Deleting item {}... {}s", item_id.0, self.elapsed_secs()))
             }
+            ModelState::DeletionError(item_id, _item_info, err) => column![
+                text(format!(
+                    "Error deleting item {} from the workshop:\n{}",
+                    item_id.0,
+                    self.error_text(err)
+                )),
+                row![
+                    button("Go Back").on_press(Message::GoBack),
+                    button("Retry").on_press(Message::Retry),
+                ],
+            ]
+            .into(),
+            ModelState::SteamLost(_) => column![
+                text("Lost connection to Steam. Please make sure Steam is running, then retry."),
+                button("Retry").on_press(Message::Retry),
+            ]
+            .into(),
             ModelState::SendingError(item_id, item_info, err) => column![text(format!(
-                "Error uploading your item to the workshop:\n{:?}\n\"{}\" is created on the workshop with ID {}, but does not have your files in it.\nPlease resolve the issue and try uploading to this existing ID again.",
-                err, item_info.name, item_id.0
+                "Error uploading your item to the workshop:\n{}\n\"{}\" is created on the workshop with ID {}, but does not have your files in it.\nPlease resolve the issue and try uploading to this existing ID again.",
+                self.upload_error_text(err), item_info.name, item_id.0
             ).as_str()),
-            button("Go Back").on_press(Message::GoBack),
+            row![
+                button("Go Back").on_press(Message::GoBack),
+                button("Retry").on_press(Message::Retry),
+            ],
             ].into(),
-            ModelState::Done(id) => column![
-                text(format!("Item ID {} uploaded to workshop.", id.0)),
-                button("Go to your item").on_press(Message::Proceed),
-                button("Restart").on_press(Message::GoBack),
-            ]
-            .into(),
+            ModelState::Done(id, visibility) => {
+                let mut res = column![text(format!("Item ID {} uploaded to workshop.", id.0))];
+                if let Some(notice) = visibility.hidden_notice() {
+                    res = res.push(text(notice).size(14));
+                }
+                let mut res = res.push(column![
+                    button("Go to your item").on_press(Message::Proceed),
+                    button("Copy link").on_press(Message::CopyItemLink),
+                    button("Restart").on_press(Message::GoBack),
+                ]);
+                if self.test_upload.is_some() {
+                    res = res.push(column![
+                        text("This was a test upload. Once you've checked the item page, you can delete it.").size(14),
+                        button("Delete test item").on_press(Message::DeleteItem),
+                    ]);
+                }
+
+                if self.legal_agreement_accepted == Some(false) {
+                    res.push(terms_view(self.legal_agreement_accepted)).into()
+                } else {
+                    res.into()
+                }
+            }
         }
     }
 }
 
+fn load_icon() -> Option<iced::window::Icon> {
+    let icon = image::load_from_memory(ICON_PNG).ok()?.into_rgba8();
+    let (width, height) = icon.dimensions();
+    iced::window::Icon::from_rgba(icon.into_raw(), width, height).ok()
+}
+
+fn exit_with_error(headless: bool, msg: &str) -> ! {
+    if headless {
+        eprintln!("{}", msg);
+    } else {
+        error_dialog(msg);
+    }
+    std::process::exit(1);
+}
+
 fn main() -> iced::Result {
-    let client = APP_ID_STR
-        .parse()
-        .map(AppId)
-        .map(WorkshopClient::init_app)
-        .expect_or_dialog("Failed to parse App ID. This build of the workshop uploader is corrupt.")
-        .expect_or_dialog("Failed to initialize Steam Workshop client.");
+    logging::init();
+    let config = Config::load();
+    let poll_interval = config.callback_poll_interval();
+    let args = match cli::Args::parse(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(err) => exit_with_error(true, format!("{}\n{}", err, cli::USAGE).as_str()),
+    };
+    let headless = args.is_headless();
+
+    let app_id = match args.app_id() {
+        Ok(Some(app_id)) => app_id,
+        Ok(None) => match APP_ID_STR.parse().map(AppId) {
+            Ok(app_id) => app_id,
+            Err(_) => exit_with_error(
+                headless,
+                "Failed to parse App ID. This build of the workshop uploader is corrupt.",
+            ),
+        },
+        Err(err) => exit_with_error(headless, err.as_str()),
+    };
+    let upload = if headless {
+        match args.upload() {
+            Ok(upload) => Some(upload),
+            Err(err) => exit_with_error(true, format!("{}\n{}", err, cli::USAGE).as_str()),
+        }
+    } else {
+        None
+    };
+
+    let client = match WorkshopClient::init_app(app_id, poll_interval) {
+        Ok(client) => client,
+        Err(err) if !my_steamworks::steam_running() => {
+            log::error!("Steam is not running: {:?}", err);
+            exit_with_error(
+                headless,
+                "Steam must be running to use this uploader. Please start Steam and try again.",
+            )
+        }
+        Err(err) => exit_with_error(
+            headless,
+            format!(
+                "Failed to initialize Steam Workshop client.\nError: {}",
+                err
+            )
+            .as_str(),
+        ),
+    };
+
+    if let Some((item_id, item_info)) = upload {
+        let code = cli::run_upload(client.clone(), item_id, item_info, &config, args.json());
+        client.shutdown();
+        std::process::exit(code);
+    }
 
     Model::run(Settings {
         id: None,
         window: iced::window::Settings {
-            size: (300, 400),
-            position: iced::window::Position::Centered,
+            size: config.window_size(),
+            position: match config.window_position() {
+                Some((x, y)) => iced::window::Position::Specific(x, y),
+                None => iced::window::Position::Centered,
+            },
             min_size: None,
             max_size: None,
             visible: true,
@@ -345,14 +2366,183 @@ fn main() -> iced::Result {
             decorations: true,
             transparent: false,
             always_on_top: false,
-            icon: None,
+            icon: load_icon(),
         },
         flags: client,
         default_font: None,
         default_text_size: 20,
         text_multithreading: false,
         antialiasing: false,
-        exit_on_close_request: true,
+        exit_on_close_request: false,
         try_opengles_first: false,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_model(state: ModelState) -> Model {
+        Model {
+            client: None,
+            app_id: AppId(571880),
+            persona_name: String::new(),
+            state,
+            folder_scan: None,
+            scan_in_progress: false,
+            config: Config::default(),
+            system_theme: ThemeChoice::Dark,
+            profiles: Profiles::default(),
+            versions: VersionHistory::default(),
+            recent_items: RecentItems::default(),
+            batch: Vec::new(),
+            batch_pause_requested: false,
+            test_upload: None,
+            form_start: None,
+            validation_report: None,
+            should_exit: false,
+            last_upload: None,
+            pending_upload: None,
+            show_folder_contents: false,
+            legal_agreement_accepted: None,
+            exclude_hidden_files: false,
+            staged_paths: Vec::new(),
+            abandoned_paths: Vec::new(),
+            busy_since: Instant::now(),
+            now: Instant::now(),
+            upload_progress: None,
+            upload_serial: 0,
+            toasts: Toasts::default(),
+        }
+    }
+
+    fn test_item_info() -> ItemInfo {
+        ItemInfo {
+            name: "Test item".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn busy_states_ignore_actions() {
+        let busy_states = [
+            ModelState::ExistingIdSearching(PublishedFileId(123), None),
+            ModelState::CreatingItem(test_item_info()),
+            ModelState::SendingItem(PublishedFileId(123), test_item_info()),
+        ];
+        let actions = [
+            Message::Proceed,
+            Message::TestUpload,
+            Message::EditItemData(ItemInfoMessage::BrowsePreviewImage),
+            Message::EditItemData(ItemInfoMessage::BrowseTargetFolder),
+            Message::EditItemData(ItemInfoMessage::EditName("Renamed".to_string())),
+            Message::SetExistingId("456".to_string()),
+        ];
+
+        for state in busy_states {
+            for action in actions.clone() {
+                let mut model = test_model(state.clone());
+                let _ = model.update(action.clone());
+                assert!(
+                    model.state == state,
+                    "{:?} changed state {}",
+                    action,
+                    model.state_name()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_upload_only_creates_new_items() {
+        let state = ModelState::ItemForm(Some(PublishedFileId(123)), test_item_info().into());
+        let mut model = test_model(state.clone());
+        let _ = model.update(Message::TestUpload);
+        assert!(model.state == state);
+        assert!(model.test_upload.is_none());
+    }
+
+    #[test]
+    fn cancelled_upload_keeps_staged_files_until_its_result() {
+        let path = std::env::temp_dir().join("workshop_uploader_test_abandoned_preview.jpg");
+        std::fs::write(&path, b"preview").unwrap();
+
+        let mut model = test_model(ModelState::Initial(String::new()));
+        model.staged_paths.push(path.clone());
+        model.abandon_staged_paths(model.upload_serial);
+        model.upload_serial += 1;
+
+        model.remove_staged_paths();
+        assert!(path.exists());
+        assert_ne!(model.unabandoned_path(path.clone()), path);
+
+        let _ = model.update(Message::ReceiveUploadError(
+            model.upload_serial - 1,
+            UploadError::Steam(SteamError::NoConnection),
+        ));
+        assert!(!path.exists());
+        assert!(model.abandoned_paths.is_empty());
+    }
+
+    #[test]
+    fn search_ignores_stale_results() {
+        let state = ModelState::ExistingIdSearching(PublishedFileId(123), None);
+        let mut model = test_model(state.clone());
+        let _ = model.update(Message::ReceiveFoundItemInfo(
+            PublishedFileId(456),
+            test_item_info(),
+            None,
+        ));
+        assert!(model.state == state);
+    }
+
+    #[test]
+    fn normalize_item_id_accepts_prefixes() {
+        assert_eq!(normalize_item_id("id:123"), "123");
+        assert_eq!(normalize_item_id("ID: 123"), "123");
+        assert_eq!(normalize_item_id("#123"), "123");
+        assert_eq!(normalize_item_id(" 123 "), "123");
+    }
+
+    #[test]
+    fn normalize_item_id_reads_links() {
+        assert_eq!(
+            normalize_item_id("https://steamcommunity.com/sharedfiles/filedetails/?id=123"),
+            "123"
+        );
+        assert_eq!(
+            normalize_item_id("steam://url/CommunityFilePage/123"),
+            "123"
+        );
+        assert_eq!(
+            normalize_item_id(
+                "https://steamcommunity.com/workshop/filedetails/?appid=571880&id=123"
+            ),
+            "123"
+        );
+        assert_eq!(
+            normalize_item_id(
+                "https://steamcommunity.com/sharedfiles/filedetails/?id=123&searchtext="
+            ),
+            "123"
+        );
+    }
+
+    #[test]
+    fn normalize_item_id_ignores_appid() {
+        assert!(
+            parse_item_id("https://steamcommunity.com/app/571880/workshop/?appid=571880").is_err()
+        );
+    }
+
+    #[test]
+    fn parse_item_id_rejects_garbage() {
+        assert!(parse_item_id("hello").is_err());
+        assert!(parse_item_id("").is_err());
+        assert!(parse_item_id("id:").is_err());
+        assert!(
+            parse_item_id("https://steamcommunity.com/sharedfiles/filedetails/?id=123abc").is_err()
+        );
+        assert!(parse_item_id("steam://url/CommunityFilePage/123abc").is_err());
+    }
+}
@@ -0,0 +1,393 @@
+use super::storage::{data_dir, read_json, write_json};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+use steamworks::PublishedFileId;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileEntry {
+    pub size: u64,
+    pub modified: u64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Manifest {
+    pub files: BTreeMap<String, FileEntry>,
+}
+
+impl Manifest {
+    pub fn scan(root: &Path) -> std::io::Result<Self> {
+        let mut manifest = Manifest::default();
+        manifest.scan_dir(root, "")?;
+        Ok(manifest)
+    }
+
+    fn scan_dir(&mut self, dir: &Path, prefix: &str) -> std::io::Result<()> {
+        for entry in std::fs::read_dir(dir)? {
+            let entry = entry?;
+            let metadata = entry.metadata()?;
+            let name = format!("{}{}", prefix, entry.file_name().to_string_lossy());
+
+            if metadata.is_dir() {
+                self.scan_dir(&entry.path(), format!("{}/", name).as_str())?;
+            } else {
+                let modified = metadata
+                    .modified()
+                    .ok()
+                    .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+                    .map(|duration| duration.as_secs())
+                    .unwrap_or(0);
+
+                self.files.insert(
+                    name,
+                    FileEntry {
+                        size: metadata.len(),
+                        modified,
+                    },
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    fn path_for(item_id: PublishedFileId) -> Option<PathBuf> {
+        data_dir().map(|dir| dir.join("manifests").join(format!("{}.json", item_id.0)))
+    }
+
+    pub fn load(item_id: PublishedFileId) -> Option<Self> {
+        Manifest::path_for(item_id).and_then(|path| read_json(&path))
+    }
+
+    pub fn save(&self, item_id: PublishedFileId) -> std::io::Result<()> {
+        match Manifest::path_for(item_id) {
+            Some(path) => write_json(&path, self),
+            None => Err(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                "No data directory available to store the upload manifest.",
+            )),
+        }
+    }
+
+    pub fn total_size(&self) -> u64 {
+        self.files.values().map(|entry| entry.size).sum()
+    }
+
+    pub fn top_level_entries(&self) -> Vec<TopLevelEntry> {
+        let mut entries: BTreeMap<&str, TopLevelEntry> = BTreeMap::new();
+
+        for (name, file) in &self.files {
+            let (top, is_dir) = match name.split_once('/') {
+                Some((dir, _)) => (dir, true),
+                None => (name.as_str(), false),
+            };
+
+            let entry = entries.entry(top).or_insert_with(|| TopLevelEntry {
+                name: top.to_string(),
+                is_dir,
+                file_count: 0,
+                size: 0,
+            });
+            entry.file_count += 1;
+            entry.size += file.size;
+        }
+
+        entries.into_values().collect()
+    }
+
+    pub fn hidden_entries(&self) -> Vec<String> {
+        self.matching_entries(|part, _| is_hidden(part))
+    }
+
+    pub fn junk_entries(&self, patterns: &[String]) -> Vec<String> {
+        self.matching_entries(|part, is_dir| {
+            patterns
+                .iter()
+                .any(|pattern| matches_pattern(pattern, part, is_dir))
+        })
+    }
+
+    fn matching_entries(&self, matches: impl Fn(&str, bool) -> bool) -> Vec<String> {
+        let mut entries = BTreeSet::new();
+
+        for name in self.files.keys() {
+            let parts: Vec<&str> = name.split('/').collect();
+            for (index, part) in parts.iter().enumerate() {
+                if matches(part, index + 1 < parts.len()) {
+                    entries.insert(parts[..=index].join("/"));
+                    break;
+                }
+            }
+        }
+
+        entries.into_iter().collect()
+    }
+
+    pub fn diff(&self, previous: &Manifest) -> ManifestDiff {
+        let mut diff = ManifestDiff::default();
+
+        for (name, entry) in &self.files {
+            match previous.files.get(name) {
+                None => diff.added.push(name.clone()),
+                Some(old_entry) if old_entry != entry => diff.modified.push(name.clone()),
+                Some(_) => (),
+            }
+        }
+
+        for name in previous.files.keys() {
+            if !self.files.contains_key(name) {
+                diff.removed.push(name.clone());
+            }
+        }
+
+        diff
+    }
+}
+
+fn is_hidden(name: &str) -> bool {
+    name.starts_with('.')
+}
+
+fn matches_pattern(pattern: &str, name: &str, is_dir: bool) -> bool {
+    let (pattern, dir_only) = match pattern.strip_suffix('/') {
+        Some(pattern) => (pattern, true),
+        None => (pattern, false),
+    };
+    if dir_only && !is_dir {
+        return false;
+    }
+
+    match pattern.strip_prefix('*') {
+        Some(suffix) => name
+            .to_lowercase()
+            .ends_with(suffix.to_lowercase().as_str()),
+        None => name.eq_ignore_ascii_case(pattern),
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExcludeFilter {
+    pub hidden: bool,
+    pub patterns: Vec<String>,
+}
+
+impl ExcludeFilter {
+    fn excludes(&self, name: &str, is_dir: bool) -> bool {
+        (self.hidden && is_hidden(name))
+            || self
+                .patterns
+                .iter()
+                .any(|pattern| matches_pattern(pattern, name, is_dir))
+    }
+}
+
+fn copy_filtered(src: &Path, dest: &Path, filter: &ExcludeFilter) -> std::io::Result<()> {
+    std::fs::create_dir_all(dest)?;
+
+    for entry in std::fs::read_dir(src)? {
+        let entry = entry?;
+        let is_dir = entry.metadata()?.is_dir();
+        if filter.excludes(&entry.file_name().to_string_lossy(), is_dir) {
+            continue;
+        }
+
+        let target = dest.join(entry.file_name());
+        if is_dir {
+            copy_filtered(&entry.path(), &target, filter)?;
+        } else {
+            std::fs::copy(entry.path(), target)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn has_excluded(dir: &Path, filter: &ExcludeFilter) -> std::io::Result<bool> {
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let is_dir = entry.metadata()?.is_dir();
+        if filter.excludes(&entry.file_name().to_string_lossy(), is_dir)
+            || (is_dir && has_excluded(&entry.path(), filter)?)
+        {
+            return Ok(true);
+        }
+    }
+
+    Ok(false)
+}
+
+pub fn staging_dir(item_id: PublishedFileId) -> PathBuf {
+    std::env::temp_dir().join(format!("workshop_uploader_staging_{}", item_id.0))
+}
+
+pub async fn stage_filtered(
+    src: PathBuf,
+    dest: PathBuf,
+    filter: ExcludeFilter,
+) -> std::io::Result<PathBuf> {
+    smol::unblock(move || {
+        // Only pay for a copy when something would actually be left out.
+        if !has_excluded(&src, &filter)? {
+            return Ok(src);
+        }
+
+        if dest.exists() {
+            std::fs::remove_dir_all(&dest)?;
+        }
+
+        copy_filtered(&src, &dest, &filter)?;
+        Ok(dest)
+    })
+    .await
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TopLevelEntry {
+    pub name: String,
+    pub is_dir: bool,
+    pub file_count: usize,
+    pub size: u64,
+}
+
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+
+    format!("{:.1} {}", value, UNITS[unit])
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ManifestDiff {
+    pub added: Vec<String>,
+    pub modified: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+impl ManifestDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.modified.is_empty() && self.removed.is_empty()
+    }
+}
+
+impl fmt::Display for ManifestDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return write!(f, "No files changed since the last upload.");
+        }
+
+        let mut parts = Vec::new();
+        if !self.modified.is_empty() {
+            let plural = if self.modified.len() == 1 { "" } else { "s" };
+            parts.push(format!("{} file{} changed", self.modified.len(), plural));
+        }
+        if !self.added.is_empty() {
+            parts.push(format!("{} added", self.added.len()));
+        }
+        if !self.removed.is_empty() {
+            parts.push(format!("{} removed", self.removed.len()));
+        }
+
+        write!(f, "{}.", parts.join(", "))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FolderScan {
+    pub folder: PathBuf,
+    pub manifest: Manifest,
+    pub changes: Option<ManifestDiff>,
+    pub hidden: Vec<String>,
+    pub junk: Vec<String>,
+}
+
+pub async fn scan_folder(
+    item_id: Option<PublishedFileId>,
+    folder: PathBuf,
+    exclude_patterns: Vec<String>,
+) -> Option<FolderScan> {
+    smol::unblock(move || {
+        let manifest = Manifest::scan(&folder).ok()?;
+        let changes = item_id
+            .and_then(Manifest::load)
+            .map(|previous| manifest.diff(&previous));
+
+        let hidden = manifest.hidden_entries();
+        let junk = manifest.junk_entries(&exclude_patterns);
+
+        Some(FolderScan {
+            folder,
+            manifest,
+            changes,
+            hidden,
+            junk,
+        })
+    })
+    .await
+}
+
+pub async fn save_folder_manifest(item_id: PublishedFileId, folder: PathBuf) {
+    let result = smol::unblock(move || Manifest::scan(&folder)?.save(item_id)).await;
+    if let Err(error) = result {
+        log::warn!("Failed to save upload manifest: {}", error);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("workshop_uploader_test_{}", name));
+        if dir.exists() {
+            std::fs::remove_dir_all(&dir).unwrap();
+        }
+        std::fs::create_dir_all(dir.join("game")).unwrap();
+        std::fs::write(dir.join("game").join("script.rpy"), b"label start:").unwrap();
+        dir
+    }
+
+    fn default_filter() -> ExcludeFilter {
+        ExcludeFilter {
+            hidden: true,
+            patterns: vec![".DS_Store".to_string(), "*.tmp".to_string()],
+        }
+    }
+
+    #[test]
+    fn stage_filtered_skips_copy_without_matches() {
+        let src = test_dir("stage_clean");
+        let dest = test_dir("stage_clean_dest").join("staged");
+
+        let staged = smol::block_on(stage_filtered(src.clone(), dest.clone(), default_filter()));
+
+        assert_eq!(staged.unwrap(), src);
+        assert!(!dest.exists());
+    }
+
+    #[test]
+    fn stage_filtered_leaves_out_matches() {
+        let src = test_dir("stage_junk");
+        std::fs::write(src.join("game").join(".DS_Store"), b"").unwrap();
+        std::fs::write(src.join("notes.tmp"), b"").unwrap();
+        let dest = test_dir("stage_junk_dest").join("staged");
+
+        let staged = smol::block_on(stage_filtered(src, dest.clone(), default_filter())).unwrap();
+
+        assert_eq!(staged, dest);
+        assert!(dest.join("game").join("script.rpy").is_file());
+        assert!(!dest.join("game").join(".DS_Store").exists());
+        assert!(!dest.join("notes.tmp").exists());
+    }
+}
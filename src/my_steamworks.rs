@@ -1,15 +1,27 @@
 use super::item_info::ItemInfo;
-use crate::err_dialog_types::confirm_dialog;
-use std::ops::Deref;
-use std::sync::{atomic::AtomicUsize, atomic::Ordering, Arc};
-use std::thread::Thread;
+use super::manifest::{self, ExcludeFilter};
+use crate::err_dialog_types::{confirm_dialog, error_dialog, FriendlyMessage};
+use std::collections::BTreeSet;
+use std::fmt;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::thread::{JoinHandle, Thread};
 use std::time::Duration;
-use steamworks::{Client, PublishedFileId, QueryResult, QueryResults, SingleClient, SteamError};
+use steamworks::{
+    AppId, Client, ClientManager, PublishedFileId, QueryResult, QueryResults, SingleClient,
+    SteamError, SteamId, UpdateStatus, UpdateWatchHandle,
+};
 
 #[derive(Debug, Clone)]
 pub struct SingleClientExecutor {
     watchers: Arc<AtomicUsize>,
+    stop: Arc<AtomicBool>,
     handle: Thread,
+    join_handle: Arc<Mutex<Option<JoinHandle<()>>>>,
 }
 
 impl SingleClientExecutor {
@@ -21,26 +33,59 @@ impl SingleClientExecutor {
     fn unwatch(&self) {
         self.watchers.fetch_sub(1, Ordering::Acquire);
     }
+
+    fn shutdown(&self) {
+        self.stop.store(true, Ordering::Release);
+        self.handle.unpark()
+    }
+
+    fn join(&self) {
+        let join_handle = self
+            .join_handle
+            .lock()
+            .ok()
+            .and_then(|mut handle| handle.take());
+        if let Some(join_handle) = join_handle {
+            if join_handle.join().is_err() {
+                log::warn!("The steamworks thread panicked before shutting down.");
+            }
+        }
+    }
 }
 
-fn start_executor(single_client: SingleClient) -> SingleClientExecutor {
+fn start_executor(single_client: SingleClient, poll_interval: Duration) -> SingleClientExecutor {
     let watchers: Arc<AtomicUsize> = Arc::default();
+    let stop: Arc<AtomicBool> = Arc::default();
     let thread_copy = watchers.clone();
+    let thread_stop = stop.clone();
 
-    let handle = std::thread::Builder::new()
+    let join_handle = std::thread::Builder::new()
         .name("SingleClientExecutor".to_string())
-        .spawn(move || steamworks_worker(single_client, thread_copy))
-        .expect("Failed to start steamworks thread.")
-        .thread()
-        .clone();
-
-    SingleClientExecutor { watchers, handle }
+        .spawn(move || steamworks_worker(single_client, thread_copy, thread_stop, poll_interval))
+        .expect("Failed to start steamworks thread.");
+
+    SingleClientExecutor {
+        watchers,
+        stop,
+        handle: join_handle.thread().clone(),
+        join_handle: Arc::new(Mutex::new(Some(join_handle))),
+    }
 }
 
-fn steamworks_worker(single_client: SingleClient, mut watchers: Arc<AtomicUsize>) {
+fn steamworks_worker(
+    single_client: SingleClient,
+    mut watchers: Arc<AtomicUsize>,
+    stop: Arc<AtomicBool>,
+    poll_interval: Duration,
+) {
     loop {
-        while watchers.load(Ordering::Acquire) > 0 {
+        if stop.load(Ordering::Acquire) {
+            return;
+        }
+
+        while watchers.load(Ordering::Acquire) > 0 && !stop.load(Ordering::Acquire) {
             single_client.run_callbacks();
+            std::thread::sleep(poll_interval);
         }
 
         std::thread::park_timeout(Duration::from_millis(100));
@@ -70,33 +115,119 @@ impl Drop for SingleClientExecutorWatcher {
     }
 }
 
+type SharedWatcher = Arc<Mutex<Option<SingleClientExecutorWatcher>>>;
+
+fn release_watcher(watcher: &SharedWatcher) {
+    if let Ok(mut watcher) = watcher.lock() {
+        watcher.take();
+    }
+}
+
 #[derive(Debug)]
 pub struct CallbackSender<T> {
-    _watcher: SingleClientExecutorWatcher,
-    sender: iced::futures::channel::oneshot::Sender<T>,
+    watcher: SharedWatcher,
+    sender: Option<iced::futures::channel::oneshot::Sender<T>>,
 }
 
 impl<T> CallbackSender<T> {
-    fn get_channel(
-        executor: SingleClientExecutor,
-    ) -> (Self, iced::futures::channel::oneshot::Receiver<T>) {
+    fn get_channel(executor: SingleClientExecutor) -> (Self, CallbackReceiver<T>) {
         let (tx, rx) = iced::futures::channel::oneshot::channel();
+        let watcher = Arc::new(Mutex::new(Some(SingleClientExecutorWatcher::new(executor))));
         let wtx = CallbackSender {
-            _watcher: SingleClientExecutorWatcher::new(executor),
-            sender: tx,
+            watcher: watcher.clone(),
+            sender: Some(tx),
+        };
+        let wrx = CallbackReceiver {
+            watcher,
+            receiver: rx,
         };
-        (wtx, rx)
+        (wtx, wrx)
     }
 
-    fn send(self, value: T) -> Result<(), T> {
-        self.sender.send(value)
+    fn send(mut self, value: T) -> Result<(), T> {
+        match self.sender.take() {
+            Some(sender) => sender.send(value),
+            None => Err(value),
+        }
     }
 }
 
-impl<T> Deref for CallbackSender<T> {
-    type Target = iced::futures::channel::oneshot::Sender<T>;
-    fn deref(&self) -> &Self::Target {
-        &self.sender
+impl<T> Drop for CallbackSender<T> {
+    fn drop(&mut self) {
+        release_watcher(&self.watcher);
+    }
+}
+
+#[derive(Debug)]
+pub struct CallbackReceiver<T> {
+    watcher: SharedWatcher,
+    receiver: iced::futures::channel::oneshot::Receiver<T>,
+}
+
+impl<T> Future for CallbackReceiver<T> {
+    type Output = Result<T, iced::futures::channel::oneshot::Canceled>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        Pin::new(&mut self.receiver).poll(cx)
+    }
+}
+
+async fn await_callback<T>(
+    rx: CallbackReceiver<Result<T, SteamError>>,
+    timeout: Duration,
+) -> Result<T, SteamError> {
+    // Steam keeps the sender alive until its callback fires, which may be never, so a
+    // timed-out call has to stop keeping the callback loop busy itself.
+    let watcher = rx.watcher.clone();
+    let result = async {
+        rx.await
+            .map_err(|iced::futures::channel::oneshot::Canceled| SteamError::Cancelled)
+            .and_then(|x| x)
+    };
+    let timer = async {
+        smol::Timer::after(timeout).await;
+        release_watcher(&watcher);
+        Err(SteamError::Timeout)
+    };
+    smol::future::or(result, timer).await
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UploadError {
+    Steam(SteamError),
+    TargetFolderMissing(PathBuf),
+    PreviewUnreadable(PathBuf),
+    PreviewCropFailed(String),
+    StagingFailed(String),
+}
+
+impl From<SteamError> for UploadError {
+    fn from(err: SteamError) -> Self {
+        UploadError::Steam(err)
+    }
+}
+
+impl fmt::Display for UploadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UploadError::Steam(err) => write!(f, "{}", err.friendly_message()),
+            UploadError::TargetFolderMissing(path) => write!(
+                f,
+                "Target folder \"{}\" is no longer available.",
+                path.to_string_lossy()
+            ),
+            UploadError::PreviewUnreadable(path) => write!(
+                f,
+                "Preview image \"{}\" is locked or unreadable.",
+                path.to_string_lossy()
+            ),
+            UploadError::PreviewCropFailed(err) => write!(f, "{}", err),
+            UploadError::StagingFailed(err) => write!(
+                f,
+                "Could not prepare a filtered copy of the target folder: {}",
+                err
+            ),
+        }
     }
 }
 
@@ -104,23 +235,91 @@ impl<T> Deref for CallbackSender<T> {
 pub struct WorkshopClient {
     callback_executor: SingleClientExecutor,
     steam_client: Client,
+    upload_watch: Arc<Mutex<Option<UpdateWatchHandle<ClientManager>>>>,
 }
 
 impl WorkshopClient {
-    pub fn init_app(id: steamworks::AppId) -> steamworks::SResult<Self> {
+    pub fn init_app(id: steamworks::AppId, poll_interval: Duration) -> steamworks::SResult<Self> {
         Client::init_app(id).map(|(client, single_client)| WorkshopClient {
-            callback_executor: start_executor(single_client),
+            callback_executor: start_executor(single_client, poll_interval),
             steam_client: client,
+            upload_watch: Arc::default(),
         })
     }
 
-    pub fn open_url(&self, url: &str) -> () {
-        self.steam_client
-            .friends()
-            .activate_game_overlay_to_web_page(url)
+    pub fn shutdown(&self) {
+        self.clear_upload_watch();
+        self.callback_executor.shutdown();
+    }
+
+    pub fn close(self) {
+        // Wait for the callback loop to let go of the API, so dropping the last clone
+        // shuts Steam down before anyone initializes it again.
+        self.shutdown();
+        self.callback_executor.join();
+    }
+
+    pub fn is_online(&self) -> bool {
+        // steamworks 0.10 still doesn't wrap ISteamUser::BLoggedOn, so ask the raw interface.
+        // The interface is valid for as long as this client keeps the API initialized.
+        unsafe {
+            steamworks::sys::SteamAPI_ISteamUser_BLoggedOn(
+                steamworks::sys::SteamAPI_SteamUser_v021(),
+            )
+        }
+    }
+
+    pub fn persona_name(&self) -> String {
+        self.steam_client.friends().name()
+    }
+
+    pub fn app_id(&self) -> AppId {
+        self.steam_client.utils().app_id()
+    }
+
+    pub fn upload_progress(&self) -> Option<(UpdateStatus, u64, u64)> {
+        self.upload_watch
+            .lock()
+            .ok()?
+            .as_ref()
+            .map(|watch| watch.progress())
+    }
+
+    pub fn clear_upload_watch(&self) {
+        self.set_upload_watch(None);
+    }
+
+    fn set_upload_watch(&self, watch: Option<UpdateWatchHandle<ClientManager>>) {
+        if let Ok(mut upload_watch) = self.upload_watch.lock() {
+            *upload_watch = watch;
+        }
+    }
+
+    pub fn is_steam_running(&self) -> bool {
+        steam_running()
     }
 
-    pub fn open_terms(&self) -> () {
+    pub fn is_overlay_enabled(&self) -> bool {
+        // Like BLoggedOn, IsOverlayEnabled isn't wrapped by the steamworks crate.
+        unsafe {
+            steamworks::sys::SteamAPI_ISteamUtils_IsOverlayEnabled(
+                steamworks::sys::SteamAPI_SteamUtils_v010(),
+            )
+        }
+    }
+
+    pub fn open_url(&self, url: &str) {
+        if self.is_overlay_enabled() {
+            self.steam_client
+                .friends()
+                .activate_game_overlay_to_web_page(url);
+        } else if let Err(error) = open::that(url) {
+            log::warn!("Failed to open {} in the browser: {}", url, error);
+            error_dialog(format!("Could not open {}\nError: {}", url, error).as_str());
+        }
+    }
+
+    pub fn open_terms(&self) {
         const STEAM_LEGAL_AGREEMENT: &str =
             "https://steamcommunity.com/sharedfiles/workshoplegalagreement";
 
@@ -130,54 +329,49 @@ impl WorkshopClient {
     pub async fn get_item_info(
         self: WorkshopClient,
         item_id: steamworks::PublishedFileId,
-    ) -> Result<ItemInfo, SteamError> {
+        allowed_app_ids: BTreeSet<u32>,
+        max_cache_age: u32,
+        timeout: Duration,
+    ) -> Result<(ItemInfo, Option<AppId>), SteamError> {
         let app_id = self.steam_client.utils().app_id();
+        let user = self.steam_client.user().steam_id();
         let (tx, rx) = CallbackSender::get_channel(self.callback_executor.clone());
 
         self.steam_client
             .ugc()
             .query_item(item_id)
             .expect("Failed to generate single item query.")
-            .allow_cached_response(360)
-            .include_long_desc(false)
+            .allow_cached_response(max_cache_age)
+            .include_long_desc(true)
             .include_children(false)
             .include_metadata(false)
             .include_additional_previews(false)
             .fetch(move |res| {
-                let _ = tx.send(res.and_then(|res| res.get(0).ok_or(SteamError::NoMatch)));
+                let _ = tx.send(res.and_then(|res| {
+                    res.get(0)
+                        .map(|result| (result, res.preview_url(0)))
+                        .ok_or(SteamError::NoMatch)
+                }));
             });
-        rx.await
-            .map_err(|iced::futures::channel::oneshot::Canceled| SteamError::Cancelled)
-            .and_then(|x|x)
-            .and_then(|res| match res.file_type {
-                steamworks::FileType::Community => Ok(res),
-                _ => Err(SteamError::NoMatch),
-            })
-            .and_then(|res| {
-                if res.consumer_app_id != Some(app_id){
-                    if confirm_dialog(format!("Found item\n\t\"{}\"\nappears to be for a different app than this uploader works with.\nYou may be blocked from uploading. Continue?",res.title).as_str()){
-                        Ok(res)
-                    }else{
-                        Err(SteamError::Cancelled)
-                    }
-                } else {
-                    Ok(res)
-                }
-            } )
-            // .and_then(|res| {
-            //         let user = self.steam_client.user().steam_id();
-            //         if res.owner != user && !confirm_dialog("This Workshop entry appears to have been made by another user.\nYou may be blocked from uploading.\nContinue?"){
-            //             // This check is, at present, not working.
-            //             println!("\nOwner: {}\nUser: {}",res.owner.raw(), user.raw());
-            //             Err(SteamError::AccessDenied)
-            //         }else{
-            //             Ok(res)
-            //         }
-            // })
-            .map(Into::<ItemInfo>::into)
-    }
-
-    pub async fn create_item(self) -> Result<(PublishedFileId, bool), SteamError> {
+        let (res, preview_url) = await_callback(rx, timeout).await?;
+        // The confirmations wait on the user, so keep them off the async executor.
+        smol::unblock(move || {
+            check_found_item(
+                res,
+                preview_url,
+                user,
+                app_id,
+                &allowed_app_ids,
+                confirm_dialog,
+            )
+        })
+        .await
+    }
+
+    pub async fn create_item(
+        self,
+        timeout: Duration,
+    ) -> Result<(PublishedFileId, bool), SteamError> {
         let app_id = self.steam_client.utils().app_id();
         let (tx, rx) = CallbackSender::get_channel(self.callback_executor.clone());
 
@@ -187,73 +381,428 @@ impl WorkshopClient {
                 let _ = tx.send(res);
             });
 
-        rx.await
-            .map_err(|iced::futures::channel::oneshot::Canceled| SteamError::Cancelled)
-            .and_then(|x| x)
+        await_callback(rx, timeout).await
+    }
+
+    pub async fn delete_item(
+        self,
+        item_id: PublishedFileId,
+        timeout: Duration,
+    ) -> Result<(), SteamError> {
+        let (tx, rx) = CallbackSender::get_channel(self.callback_executor.clone());
+
+        self.steam_client.ugc().delete_item(item_id, move |res| {
+            let _ = tx.send(res);
+        });
+
+        await_callback(rx, timeout).await
     }
 
     pub async fn send_item(
         self,
         item_id: PublishedFileId,
         item_info: ItemInfo,
-    ) -> Result<(PublishedFileId, bool), SteamError> {
+    ) -> Result<(PublishedFileId, bool), UploadError> {
+        let item_info = check_upload_paths(item_info).await?;
+
         let rx = {
             let app_id = self.steam_client.utils().app_id();
 
-            let change_notes = if item_info.change_notes.is_empty() {
+            let change_notes = item_info.full_change_notes();
+            let change_notes = if change_notes.is_empty() {
                 None
             } else {
-                Some(item_info.change_notes.as_str())
+                Some(change_notes.as_str())
             };
 
             let mut update_handle = self
                 .steam_client
                 .ugc()
                 .start_item_update(app_id, item_id)
-                .title(item_info.name.as_str())
-                .content_path(&item_info.target_folder);
+                .title(item_info.name.as_str());
+
+            if !item_info.metadata_only {
+                update_handle = update_handle.content_path(&item_info.target_folder)
+            }
 
             if item_info.preview_image.exists() {
                 update_handle = update_handle.preview_path(&item_info.preview_image)
             }
 
+            let description_changed = match &item_info.loaded_description {
+                Some(loaded) => *loaded != item_info.description,
+                None => !item_info.description.is_empty(),
+            };
+            if description_changed {
+                update_handle = update_handle.description(item_info.description.as_str())
+            }
+
+            if let Some(visibility) = item_info.visibility.steam_visibility() {
+                update_handle = update_handle.visibility(visibility)
+            }
+
+            let tags_changed = match &item_info.loaded_tags {
+                Some(loaded) => *loaded != item_info.tags,
+                None => !item_info.tags.is_empty(),
+            };
+            if tags_changed {
+                update_handle = update_handle.tags(item_info.tags.clone())
+            }
+
             let (tx, rx) = CallbackSender::get_channel(self.callback_executor.clone());
 
-            let _update_watch_handle = update_handle.submit(change_notes, move |res| {
+            self.set_upload_watch(Some(update_handle.submit(change_notes, move |res| {
                 let _ = tx.send(res);
-            });
+            })));
 
             rx
         };
 
+        // No timeout here: a large content upload can legitimately take far longer than any
+        // Steam query, and the user can cancel it from the progress screen instead.
         rx.await
             .map_err(|iced::futures::channel::oneshot::Canceled| SteamError::Cancelled)
             .and_then(|x| x)
+            .map_err(UploadError::Steam)
+    }
+
+    pub async fn send_filtered_item(
+        self,
+        item_id: PublishedFileId,
+        item_info: ItemInfo,
+        filter: ExcludeFilter,
+        staging_dir: Option<PathBuf>,
+    ) -> Result<(PublishedFileId, bool), UploadError> {
+        let mut item_info = check_upload_paths(item_info).await?;
+        if let Some(staging_dir) = staging_dir {
+            item_info.target_folder =
+                manifest::stage_filtered(item_info.target_folder, staging_dir, filter)
+                    .await
+                    .map_err(|err| UploadError::StagingFailed(err.to_string()))?;
+        }
+        self.send_item(item_id, item_info).await
+    }
+
+    pub async fn send_preview(
+        self,
+        item_id: PublishedFileId,
+        preview_image: PathBuf,
+    ) -> Result<(PublishedFileId, bool), UploadError> {
+        let preview_image =
+            smol::unblock(move || check_readable(&preview_image).map(|()| preview_image)).await?;
+
+        let rx = {
+            let app_id = self.steam_client.utils().app_id();
+            let (tx, rx) = CallbackSender::get_channel(self.callback_executor.clone());
+
+            self.set_upload_watch(Some(
+                self.steam_client
+                    .ugc()
+                    .start_item_update(app_id, item_id)
+                    .preview_path(&preview_image)
+                    .submit(None, move |res| {
+                        let _ = tx.send(res);
+                    }),
+            ));
+
+            rx
+        };
+
+        rx.await
+            .map_err(|iced::futures::channel::oneshot::Canceled| SteamError::Cancelled)
+            .and_then(|x| x)
+            .map_err(UploadError::Steam)
     }
 }
 
+fn check_found_item(
+    res: QueryResult,
+    preview_url: Option<String>,
+    user: SteamId,
+    app_id: AppId,
+    allowed_app_ids: &BTreeSet<u32>,
+    confirm: impl Fn(&str) -> bool,
+) -> Result<(ItemInfo, Option<AppId>), SteamError> {
+    if res.file_type != steamworks::FileType::Community {
+        return Err(SteamError::NoMatch);
+    }
+
+    // The query reports a full 64-bit SteamID, so compare only the account part.
+    let other_owner = format!(
+        "Found item\n\t\"{}\"\nappears to have been made by another user.\nYou may be blocked from uploading. Continue?",
+        res.title
+    );
+    if res.owner.account_id() != user.account_id() && !confirm(&other_owner) {
+        log::debug!("Owner: {}, user: {}", res.owner.raw(), user.raw());
+        return Err(SteamError::Cancelled);
+    }
+
+    let item_app_id = res.consumer_app_id;
+    let other_app = format!(
+        "Found item\n\t\"{}\"\nappears to be for a different app than this uploader works with.\nYou may be blocked from uploading. Continue?",
+        res.title
+    );
+    let always_allow = if item_app_id == Some(app_id)
+        || item_app_id.is_some_and(|id| allowed_app_ids.contains(&id.0))
+    {
+        None
+    } else if confirm(&other_app) {
+        item_app_id.filter(|id| {
+            confirm(&format!(
+                "Always allow items for app ID {} without asking?",
+                id.0
+            ))
+        })
+    } else {
+        return Err(SteamError::Cancelled);
+    };
+
+    let mut item_info = ItemInfo::from(res);
+    item_info.preview_url = preview_url;
+    Ok((item_info, always_allow))
+}
+
+pub fn steam_running() -> bool {
+    // Safe to call before the API is initialized; it only looks for the Steam process.
+    unsafe { steamworks::sys::SteamAPI_IsSteamRunning() }
+}
+
+pub async fn check_upload_paths(item_info: ItemInfo) -> Result<ItemInfo, UploadError> {
+    smol::unblock(move || {
+        // The folder may have vanished (or a network drive disconnected) since validation.
+        if !item_info.metadata_only && !item_info.target_folder.is_dir() {
+            return Err(UploadError::TargetFolderMissing(item_info.target_folder));
+        }
+
+        if item_info.preview_image.exists() {
+            check_readable(&item_info.preview_image)?;
+        }
+
+        Ok(item_info)
+    })
+    .await
+}
+
+fn check_readable(path: &Path) -> Result<(), UploadError> {
+    std::fs::File::open(path)
+        .map(|_| ())
+        .map_err(|_| UploadError::PreviewUnreadable(path.to_path_buf()))
+}
+
 fn _debug_query_result(result: QueryResult) {
-    println!(
+    log::debug!(
         "QueryResult: \"{}\" ({})",
-        result.title, result.published_file_id.0
+        result.title,
+        result.published_file_id.0
     );
-    println!("Owner: {}", result.owner.raw());
-    println!(
+    log::debug!("Owner: {}", result.owner.raw());
+    log::debug!(
         "Description: {} words",
-        result.description.split_whitespace().into_iter().count()
+        result.description.split_whitespace().count()
     );
-    println!("File type: {:?}", result.file_type);
+    log::debug!("File type: {:?}", result.file_type);
 }
 
 fn _debug_query_results(results: &QueryResults) {
-    println!("QueryResults: (FromCache: {})", results.was_cached());
+    log::debug!("QueryResults: (FromCache: {})", results.was_cached());
     let result_count = results.total_results();
     for (i, result) in results.iter().enumerate() {
         if let Some(result) = result {
-            println!("Result {}/{}", i, result_count);
+            log::debug!("Result {}/{}", i, result_count);
             _debug_query_result(result);
         } else {
-            println!("Result #{}: None", i);
+            log::debug!("Result #{}: None", i);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    const USER: u64 = 76561197960265729;
+    const OTHER_USER: u64 = 76561197960265730;
+
+    fn test_executor() -> SingleClientExecutor {
+        SingleClientExecutor {
+            watchers: Arc::default(),
+            stop: Arc::default(),
+            handle: std::thread::current(),
+            join_handle: Arc::default(),
+        }
+    }
+
+    fn test_query_result(owner: u64, consumer_app_id: u32) -> QueryResult {
+        QueryResult {
+            published_file_id: PublishedFileId(123),
+            creator_app_id: Some(AppId(consumer_app_id)),
+            consumer_app_id: Some(AppId(consumer_app_id)),
+            title: "Test item".to_string(),
+            description: String::new(),
+            owner: SteamId::from_raw(owner),
+            time_created: 0,
+            time_updated: 0,
+            banned: false,
+            accepted_for_use: true,
+            tags: Vec::new(),
+            tags_truncated: false,
+            file_type: steamworks::FileType::Community,
+            file_size: 0,
+            url: String::new(),
+            num_upvotes: 0,
+            num_downvotes: 0,
+            score: 0.0,
+            num_children: 0,
+        }
+    }
+
+    #[test]
+    fn declining_wrong_app_releases_watcher() {
+        let executor = test_executor();
+        let (tx, rx) = CallbackSender::get_channel(executor.clone());
+        assert_eq!(executor.watchers.load(Ordering::Acquire), 1);
+
+        let _ = tx.send(Ok((test_query_result(USER, 1), None)));
+        assert_eq!(executor.watchers.load(Ordering::Acquire), 0);
+
+        let (res, preview_url) =
+            smol::block_on(await_callback(rx, Duration::from_secs(1))).unwrap();
+        let asked = Cell::new(0);
+        let result = check_found_item(
+            res,
+            preview_url,
+            SteamId::from_raw(USER),
+            AppId(571880),
+            &BTreeSet::new(),
+            |_| {
+                asked.set(asked.get() + 1);
+                false
+            },
+        );
+
+        assert_eq!(result, Err(SteamError::Cancelled));
+        assert_eq!(asked.get(), 1);
+        assert_eq!(executor.watchers.load(Ordering::Acquire), 0);
+    }
+
+    #[test]
+    fn declining_other_owner_stops_before_app_check() {
+        let asked = Cell::new(0);
+        let result = check_found_item(
+            test_query_result(OTHER_USER, 1),
+            None,
+            SteamId::from_raw(USER),
+            AppId(571880),
+            &BTreeSet::new(),
+            |_| {
+                asked.set(asked.get() + 1);
+                false
+            },
+        );
+
+        assert_eq!(result, Err(SteamError::Cancelled));
+        assert_eq!(asked.get(), 1);
+    }
+
+    #[test]
+    fn matching_item_needs_no_confirmation() {
+        let result = check_found_item(
+            test_query_result(USER, 571880),
+            None,
+            SteamId::from_raw(USER),
+            AppId(571880),
+            &BTreeSet::new(),
+            |_| panic!("no confirmation expected"),
+        );
+
+        assert_eq!(
+            result.map(|(item_info, _)| item_info.name),
+            Ok("Test item".to_string())
+        );
+    }
+
+    #[test]
+    fn dropped_callback_releases_watcher() {
+        let executor = test_executor();
+        let (tx, rx) = CallbackSender::<Result<(), SteamError>>::get_channel(executor.clone());
+        drop(tx);
+
+        assert_eq!(executor.watchers.load(Ordering::Acquire), 0);
+        assert_eq!(
+            smol::block_on(await_callback(rx, Duration::from_secs(1))),
+            Err(SteamError::Cancelled)
+        );
+    }
+
+    #[test]
+    fn timed_out_callback_releases_watcher() {
+        let executor = test_executor();
+        let (_tx, rx) = CallbackSender::<Result<(), SteamError>>::get_channel(executor.clone());
+
+        assert_eq!(
+            smol::block_on(await_callback(rx, Duration::from_millis(10))),
+            Err(SteamError::Timeout)
+        );
+        assert_eq!(executor.watchers.load(Ordering::Acquire), 0);
+    }
+
+    #[test]
+    fn check_upload_paths_rejects_vanished_folder() {
+        let folder = std::env::temp_dir().join("workshop_uploader_test_vanished_folder");
+        std::fs::create_dir_all(&folder).unwrap();
+        let mut item_info = ItemInfo {
+            name: "Test item".to_string(),
+            target_folder: folder.clone(),
+            ..Default::default()
+        };
+        assert!(smol::block_on(check_upload_paths(item_info.clone())).is_ok());
+
+        std::fs::remove_dir(&folder).unwrap();
+        assert_eq!(
+            smol::block_on(check_upload_paths(item_info.clone())),
+            Err(UploadError::TargetFolderMissing(folder))
+        );
+
+        item_info.metadata_only = true;
+        assert!(smol::block_on(check_upload_paths(item_info)).is_ok());
+    }
+
+    #[test]
+    fn check_readable_rejects_missing_file() {
+        let path = std::env::temp_dir().join("workshop_uploader_test_missing_preview.png");
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(
+            check_readable(&path),
+            Err(UploadError::PreviewUnreadable(path.clone()))
+        );
+    }
+
+    #[test]
+    fn check_readable_accepts_readable_file() {
+        let path = std::env::temp_dir().join("workshop_uploader_test_readable_preview.png");
+        std::fs::write(&path, b"preview").unwrap();
+        assert_eq!(check_readable(&path), Ok(()));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn check_readable_rejects_unreadable_file() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let path = std::env::temp_dir().join("workshop_uploader_test_locked_preview.png");
+        std::fs::write(&path, b"preview").unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o000)).unwrap();
+
+        // Root ignores file permissions, so there's nothing to check there.
+        if std::fs::File::open(&path).is_err() {
+            let error = check_readable(&path).unwrap_err();
+            assert_eq!(error, UploadError::PreviewUnreadable(path.clone()));
+            assert!(error.to_string().contains("is locked or unreadable"));
         }
+
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).unwrap();
+        std::fs::remove_file(&path).unwrap();
     }
 }
@@ -0,0 +1,113 @@
+use super::manifest::format_bytes;
+use image::{ImageFormat, ImageResult};
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use steamworks::PublishedFileId;
+
+pub const MAX_PREVIEW_BYTES: u64 = 1024 * 1024;
+const MAX_DOWNLOAD_BYTES: u64 = 4 * 1024 * 1024;
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(15);
+
+pub fn dimensions(path: &Path) -> Option<(u32, u32)> {
+    image::image_dimensions(path).ok()
+}
+
+pub fn has_image_signature(path: &Path) -> bool {
+    const SIGNATURES: [&[u8]; 4] = [b"\xFF\xD8\xFF", b"\x89PNG\r\n\x1A\n", b"GIF87a", b"GIF89a"];
+
+    let mut header = [0; 8];
+    let read = File::open(path)
+        .and_then(|mut file| file.read(&mut header))
+        .unwrap_or(0);
+
+    SIGNATURES
+        .iter()
+        .any(|signature| header[..read].starts_with(signature))
+}
+
+pub fn is_square((width, height): (u32, u32)) -> bool {
+    width == height
+}
+
+pub fn cropped_path(item_id: PublishedFileId) -> PathBuf {
+    std::env::temp_dir().join(format!("workshop_uploader_preview_{}.jpg", item_id.0))
+}
+
+pub async fn crop_to_square(path: PathBuf, cropped_path: PathBuf) -> Result<PathBuf, String> {
+    smol::unblock(move || {
+        crop_image(&path, &cropped_path)
+            .map_err(|err| format!("Could not crop the preview image: {}", err))?;
+        check_cropped_size(&cropped_path)?;
+        Ok(cropped_path)
+    })
+    .await
+}
+
+fn check_cropped_size(path: &Path) -> Result<(), String> {
+    let size = path.metadata().map(|metadata| metadata.len()).unwrap_or(0);
+    if size > MAX_PREVIEW_BYTES {
+        return Err(format!(
+            "The cropped preview image is {}; Steam's limit is {}. Crop or shrink the image yourself and pick the smaller file.",
+            format_bytes(size),
+            format_bytes(MAX_PREVIEW_BYTES)
+        ));
+    }
+    Ok(())
+}
+
+fn crop_image(path: &Path, cropped_path: &Path) -> ImageResult<()> {
+    let img = image::open(path)?;
+    let side = img.width().min(img.height());
+    let x = (img.width() - side) / 2;
+    let y = (img.height() - side) / 2;
+
+    img.crop_imm(x, y, side, side)
+        .to_rgb8()
+        .save_with_format(cropped_path, ImageFormat::Jpeg)
+}
+
+pub async fn download(url: String) -> Option<Vec<u8>> {
+    smol::unblock(move || {
+        let response = ureq::get(&url).timeout(DOWNLOAD_TIMEOUT).call().ok()?;
+        let mut bytes = Vec::new();
+        response
+            .into_reader()
+            .take(MAX_DOWNLOAD_BYTES)
+            .read_to_end(&mut bytes)
+            .ok()?;
+        Some(bytes)
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn crop_makes_a_square_jpeg() {
+        let source = std::env::temp_dir().join("workshop_uploader_test_crop_source.png");
+        let cropped = std::env::temp_dir().join("workshop_uploader_test_crop_result.jpg");
+        image::RgbImage::new(40, 20).save(&source).unwrap();
+
+        let result = smol::block_on(crop_to_square(source.clone(), cropped.clone()));
+        assert_eq!(result, Ok(cropped.clone()));
+        assert_eq!(dimensions(&cropped), Some((20, 20)));
+
+        let _ = std::fs::remove_file(source);
+        let _ = std::fs::remove_file(cropped);
+    }
+
+    #[test]
+    fn oversized_crop_is_rejected() {
+        let path = std::env::temp_dir().join("workshop_uploader_test_large_crop.jpg");
+        std::fs::write(&path, vec![0; MAX_PREVIEW_BYTES as usize + 1]).unwrap();
+
+        let error = check_cropped_size(&path).unwrap_err();
+        assert!(error.contains("Steam's limit is"));
+
+        let _ = std::fs::remove_file(path);
+    }
+}
@@ -0,0 +1,195 @@
+use super::item_info::{normalize_name, ItemInfo};
+use super::storage::{data_dir, read_json, write_json};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use steamworks::PublishedFileId;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Profile {
+    pub name: String,
+    pub item_id: Option<u64>,
+    pub item_info: ItemInfo,
+}
+
+impl Profile {
+    pub fn new(item_id: Option<PublishedFileId>, mut item_info: ItemInfo) -> Self {
+        item_info.change_notes.clear();
+        item_info.crop_preview = false;
+        item_info.version = None;
+
+        Profile {
+            name: item_info.name.clone(),
+            item_id: item_id.map(|id| id.0),
+            item_info,
+        }
+    }
+
+    pub fn pending(item_id: PublishedFileId, item_info: ItemInfo) -> Self {
+        Profile {
+            name: item_info.name.clone(),
+            item_id: Some(item_id.0),
+            item_info,
+        }
+    }
+
+    pub fn queued(item_id: Option<PublishedFileId>, item_info: ItemInfo) -> Self {
+        Profile {
+            name: item_info.name.clone(),
+            item_id: item_id.map(|id| id.0),
+            item_info,
+        }
+    }
+
+    pub fn item_id(&self) -> Option<PublishedFileId> {
+        self.item_id.map(PublishedFileId)
+    }
+
+    pub fn export(&self, path: &Path) -> std::io::Result<()> {
+        write_json(path, self)
+    }
+
+    pub fn import(path: &Path) -> Result<Self, String> {
+        let file = File::open(path)
+            .map_err(|err| format!("Couldn't open \"{}\": {}", path.to_string_lossy(), err))?;
+        serde_json::from_reader(BufReader::new(file)).map_err(|err| {
+            format!(
+                "\"{}\" is not a valid preset file: {}",
+                path.to_string_lossy(),
+                err
+            )
+        })
+    }
+
+    fn pending_upload_path() -> Option<PathBuf> {
+        data_dir().map(|dir| dir.join("pending_upload.json"))
+    }
+
+    pub fn load_pending_upload() -> Option<Self> {
+        Profile::pending_upload_path().and_then(|path| read_json(&path))
+    }
+
+    pub fn save_pending_upload(&self) {
+        let result = match Profile::pending_upload_path() {
+            Some(path) => write_json(&path, self),
+            None => return,
+        };
+
+        if let Err(error) = result {
+            log::warn!("Failed to save pending upload: {}", error);
+        }
+    }
+
+    pub fn clear_pending_upload() {
+        if let Some(path) = Profile::pending_upload_path().filter(|path| path.exists()) {
+            if let Err(error) = std::fs::remove_file(path) {
+                log::warn!("Failed to clear pending upload: {}", error);
+            }
+        }
+    }
+
+    fn batch_queue_path() -> Option<PathBuf> {
+        data_dir().map(|dir| dir.join("batch_queue.json"))
+    }
+
+    pub fn load_batch_queue() -> Vec<Self> {
+        Profile::batch_queue_path()
+            .and_then(|path| read_json(&path))
+            .unwrap_or_default()
+    }
+
+    pub fn save_batch_queue(queue: &[Self]) {
+        let path = match Profile::batch_queue_path() {
+            Some(path) => path,
+            None => return,
+        };
+
+        let result = if queue.is_empty() {
+            match std::fs::remove_file(&path) {
+                Err(error) if error.kind() != std::io::ErrorKind::NotFound => Err(error),
+                _ => Ok(()),
+            }
+        } else {
+            write_json(&path, &queue)
+        };
+
+        if let Err(error) = result {
+            log::warn!("Failed to save batch queue: {}", error);
+        }
+    }
+
+    fn last_upload_path() -> Option<PathBuf> {
+        data_dir().map(|dir| dir.join("last_upload.json"))
+    }
+
+    pub fn load_last_upload() -> Option<Self> {
+        Profile::last_upload_path().and_then(|path| read_json(&path))
+    }
+
+    pub fn save_last_upload(&self) {
+        let result = match Profile::last_upload_path() {
+            Some(path) => write_json(&path, self),
+            None => return,
+        };
+
+        if let Err(error) = result {
+            log::warn!("Failed to save last upload: {}", error);
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Profiles {
+    pub profiles: Vec<Profile>,
+}
+
+impl Profiles {
+    fn path() -> Option<PathBuf> {
+        data_dir().map(|dir| dir.join("profiles.json"))
+    }
+
+    pub fn load() -> Self {
+        Profiles::path()
+            .and_then(|path| read_json(&path))
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) {
+        let result = match Profiles::path() {
+            Some(path) => write_json(&path, self),
+            None => return,
+        };
+
+        if let Err(error) = result {
+            log::warn!("Failed to save profiles: {}", error);
+        }
+    }
+
+    pub fn names(&self) -> Vec<String> {
+        self.profiles
+            .iter()
+            .map(|profile| profile.name.clone())
+            .collect()
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Profile> {
+        let name = normalize_name(name);
+        self.profiles
+            .iter()
+            .find(|profile| normalize_name(&profile.name) == name)
+    }
+
+    pub fn insert(&mut self, profile: Profile) {
+        let name = normalize_name(&profile.name);
+        match self
+            .profiles
+            .iter_mut()
+            .find(|p| normalize_name(&p.name) == name)
+        {
+            Some(existing) => *existing = profile,
+            None => self.profiles.push(profile),
+        }
+    }
+}
@@ -0,0 +1,79 @@
+use super::storage::{data_dir, read_json, write_json};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::PathBuf;
+use steamworks::PublishedFileId;
+
+const MAX_RECENT_ITEMS: usize = 10;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecentItem {
+    pub id: u64,
+    pub name: String,
+}
+
+impl RecentItem {
+    pub fn item_id(&self) -> PublishedFileId {
+        PublishedFileId(self.id)
+    }
+}
+
+impl fmt::Display for RecentItem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.name.is_empty() {
+            write!(f, "{}", self.id)
+        } else {
+            write!(f, "{} ({})", self.name, self.id)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RecentItems {
+    pub items: Vec<RecentItem>,
+}
+
+impl RecentItems {
+    fn path() -> Option<PathBuf> {
+        data_dir().map(|dir| dir.join("recent_items.json"))
+    }
+
+    pub fn load() -> Self {
+        RecentItems::path()
+            .and_then(|path| read_json(&path))
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) {
+        let result = match RecentItems::path() {
+            Some(path) => write_json(&path, self),
+            None => return,
+        };
+
+        if let Err(error) = result {
+            log::warn!("Failed to save recent items: {}", error);
+        }
+    }
+
+    pub fn push(&mut self, item_id: PublishedFileId, mut name: String) {
+        if let Some(index) = self.items.iter().position(|item| item.id == item_id.0) {
+            let previous = self.items.remove(index);
+            if name.is_empty() {
+                name = previous.name;
+            }
+        }
+        self.items.insert(
+            0,
+            RecentItem {
+                id: item_id.0,
+                name,
+            },
+        );
+        self.items.truncate(MAX_RECENT_ITEMS);
+    }
+
+    pub fn remove(&mut self, item_id: PublishedFileId) {
+        self.items.retain(|item| item.id != item_id.0);
+    }
+}
@@ -0,0 +1,84 @@
+use serde::{Deserialize, Serialize};
+use std::process::Command;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ThemeChoice {
+    Light,
+    Dark,
+}
+
+impl ThemeChoice {
+    pub fn toggled(self) -> Self {
+        match self {
+            ThemeChoice::Light => ThemeChoice::Dark,
+            ThemeChoice::Dark => ThemeChoice::Light,
+        }
+    }
+
+    pub fn theme(self) -> iced::Theme {
+        match self {
+            ThemeChoice::Light => iced::Theme::Light,
+            ThemeChoice::Dark => iced::Theme::Dark,
+        }
+    }
+
+    pub async fn system() -> Self {
+        let detected = smol::unblock(detect_system).await;
+        log::debug!("Detected system theme: {:?}", detected);
+        detected.unwrap_or(ThemeChoice::Dark)
+    }
+}
+
+fn command_output(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program).args(args).output().ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+#[cfg(target_os = "windows")]
+fn detect_system() -> Option<ThemeChoice> {
+    let output = command_output(
+        "reg",
+        &[
+            "query",
+            r"HKCU\Software\Microsoft\Windows\CurrentVersion\Themes\Personalize",
+            "/v",
+            "AppsUseLightTheme",
+        ],
+    )?;
+
+    if output.contains("0x1") {
+        Some(ThemeChoice::Light)
+    } else if output.contains("0x0") {
+        Some(ThemeChoice::Dark)
+    } else {
+        None
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn detect_system() -> Option<ThemeChoice> {
+    // The key only exists while dark mode is on.
+    match command_output("defaults", &["read", "-g", "AppleInterfaceStyle"]) {
+        Some(output) if output.trim() == "Dark" => Some(ThemeChoice::Dark),
+        _ => Some(ThemeChoice::Light),
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn detect_system() -> Option<ThemeChoice> {
+    let output = command_output(
+        "gsettings",
+        &["get", "org.gnome.desktop.interface", "color-scheme"],
+    )?;
+
+    if output.contains("dark") {
+        Some(ThemeChoice::Dark)
+    } else if output.contains("light") {
+        Some(ThemeChoice::Light)
+    } else {
+        None
+    }
+}
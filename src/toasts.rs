@@ -0,0 +1,38 @@
+use iced::widget::{column, container, text};
+use iced::Element;
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+const TOAST_DURATION: Duration = Duration::from_secs(3);
+const MAX_TOASTS: usize = 3;
+
+#[derive(Debug, Default)]
+pub struct Toasts {
+    items: VecDeque<(String, Instant)>,
+}
+
+impl Toasts {
+    pub fn push(&mut self, message: impl Into<String>) {
+        if self.items.len() == MAX_TOASTS {
+            self.items.pop_front();
+        }
+        self.items
+            .push_back((message.into(), Instant::now() + TOAST_DURATION));
+    }
+
+    pub fn expire(&mut self, now: Instant) {
+        self.items.retain(|(_, expires)| *expires > now);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn view<'a, Message: 'a>(&self) -> Element<'a, Message> {
+        let mut res = column![];
+        for (message, _) in &self.items {
+            res = res.push(container(text(message).size(14)).padding(4));
+        }
+        res.into()
+    }
+}
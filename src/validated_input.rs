@@ -0,0 +1,24 @@
+use iced::widget::{column, text, text_input};
+use iced::{Color, Element};
+
+pub const ERROR_COLOR: Color = Color::from_rgb(0.8, 0.1, 0.1);
+
+pub fn validated_text_input<'a, Message: Clone + 'a>(
+    placeholder: &str,
+    value: &str,
+    validator: impl Fn(&str) -> Result<(), String>,
+    on_change: impl Fn(String) -> Message + 'a,
+    on_submit: Option<Message>,
+) -> Element<'a, Message> {
+    let mut input = text_input(placeholder, value, on_change);
+    if let Some(on_submit) = on_submit {
+        input = input.on_submit(on_submit);
+    }
+
+    let mut res = column![input];
+    if let Err(error) = validator(value) {
+        res = res.push(text(error).style(ERROR_COLOR));
+    }
+
+    res.into()
+}
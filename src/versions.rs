@@ -0,0 +1,55 @@
+use super::storage::{data_dir, read_json, write_json};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use steamworks::PublishedFileId;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VersionHistory {
+    pub versions: BTreeMap<u64, String>,
+}
+
+impl VersionHistory {
+    fn path() -> Option<PathBuf> {
+        data_dir().map(|dir| dir.join("versions.json"))
+    }
+
+    pub fn load() -> Self {
+        VersionHistory::path()
+            .and_then(|path| read_json(&path))
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) {
+        let result = match VersionHistory::path() {
+            Some(path) => write_json(&path, self),
+            None => return,
+        };
+
+        if let Err(error) = result {
+            log::warn!("Failed to save version history: {}", error);
+        }
+    }
+
+    pub fn get(&self, item_id: PublishedFileId) -> Option<&str> {
+        self.versions.get(&item_id.0).map(String::as_str)
+    }
+
+    pub fn set(&mut self, item_id: PublishedFileId, version: String) {
+        self.versions.insert(item_id.0, version);
+    }
+}
+
+pub fn bump_patch(version: &str) -> String {
+    match version.rsplit_once('.') {
+        Some((head, patch)) => match patch.parse::<u64>() {
+            Ok(patch) => format!("{}.{}", head, patch + 1),
+            Err(_) => version.to_string(),
+        },
+        None => match version.parse::<u64>() {
+            Ok(patch) => (patch + 1).to_string(),
+            Err(_) => version.to_string(),
+        },
+    }
+}